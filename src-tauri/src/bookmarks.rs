@@ -0,0 +1,317 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::error::LimniError;
+use crate::storage::{data_dir, read_json_with_backup, write_json_with_backup};
+use crate::favicon::fetch_favicon_backend;
+
+/// Имя файла закладок в директории данных приложения
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// Тип узла дерева закладок
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkKind {
+    Folder,
+    Bookmark,
+}
+
+/// Узел дерева закладок: папка или закладка.
+/// `parent_id == None` означает корневой уровень
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkNode {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub kind: BookmarkKind,
+    pub title: String,
+    pub url: Option<String>,
+    pub favicon: Option<String>,
+    pub tags: Vec<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Формат файла закладок на диске
+#[derive(Default, Serialize, Deserialize)]
+struct BookmarksFile {
+    nodes: Vec<BookmarkNode>,
+}
+
+/// Хранилище закладок. Узлы лежат плоским списком,
+/// порядок среди соседей (узлов одного родителя) совпадает с порядком в списке
+#[derive(Default)]
+pub struct BookmarkStore {
+    path: Option<PathBuf>,
+    nodes: Vec<BookmarkNode>,
+    next_id: u64,
+}
+
+impl BookmarkStore {
+    /// Загружает закладки из файла или его резервной копии (пустое дерево, если нет обоих)
+    pub fn load(path: PathBuf) -> Self {
+        let file: BookmarksFile = read_json_with_backup(&path).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load bookmarks, starting empty: {}", e);
+            BookmarksFile::default()
+        });
+
+        Self { path: Some(path), nodes: file.nodes, next_id: 0 }
+    }
+
+    /// Сохраняет закладки на диск
+    pub fn save(&self) -> Result<(), LimniError> {
+        match &self.path {
+            Some(path) => write_json_with_backup(path, &BookmarksFile { nodes: self.nodes.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    /// Все узлы в порядке отображения
+    pub fn nodes(&self) -> &[BookmarkNode] {
+        &self.nodes
+    }
+
+    pub fn get(&self, id: &str) -> Option<&BookmarkNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut BookmarkNode, LimniError> {
+        self.nodes.iter_mut().find(|n| n.id == id).ok_or_else(|| LimniError::not_found("Bookmark", id))
+    }
+
+    fn generate_id(&mut self, now: u64) -> String {
+        self.next_id += 1;
+        format!("bm_{}_{}", now, self.next_id)
+    }
+
+    /// Проверяет, что родитель существует и является папкой
+    fn check_parent(&self, parent_id: Option<&str>) -> Result<(), LimniError> {
+        match parent_id {
+            None => Ok(()),
+            Some(id) => match self.get(id) {
+                Some(node) if node.kind == BookmarkKind::Folder => Ok(()),
+                Some(_) => Err(LimniError::invalid_argument("Parent is not a folder")),
+                None => Err(LimniError::not_found("Folder", id)),
+            },
+        }
+    }
+
+    /// Вставляет узел в конец списка детей родителя или перед соседом с номером `index`
+    fn insert_at(&mut self, node: BookmarkNode, index: Option<usize>) {
+        let sibling_positions: Vec<usize> = self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.parent_id == node.parent_id)
+            .map(|(i, _)| i)
+            .collect();
+
+        let position = match index.and_then(|i| sibling_positions.get(i)) {
+            Some(&pos) => pos,
+            None => sibling_positions.last().map(|&pos| pos + 1).unwrap_or(self.nodes.len()),
+        };
+        self.nodes.insert(position, node);
+    }
+
+    /// Добавляет закладку
+    pub fn add_bookmark(
+        &mut self,
+        parent_id: Option<String>,
+        title: &str,
+        url: &str,
+        tags: Vec<String>,
+        now: u64,
+    ) -> Result<BookmarkNode, LimniError> {
+        self.check_parent(parent_id.as_deref())?;
+        url::Url::parse(url).map_err(|e| LimniError::invalid_url(url, e))?;
+
+        let node = BookmarkNode {
+            id: self.generate_id(now),
+            parent_id,
+            kind: BookmarkKind::Bookmark,
+            title: title.trim().to_string(),
+            url: Some(url.to_string()),
+            favicon: None,
+            tags: normalize_tags(tags),
+            created_at: now,
+            updated_at: now,
+        };
+        self.insert_at(node.clone(), None);
+        Ok(node)
+    }
+
+    /// Создаёт папку
+    pub fn add_folder(&mut self, parent_id: Option<String>, title: &str, now: u64) -> Result<BookmarkNode, LimniError> {
+        self.check_parent(parent_id.as_deref())?;
+
+        let node = BookmarkNode {
+            id: self.generate_id(now),
+            parent_id,
+            kind: BookmarkKind::Folder,
+            title: title.trim().to_string(),
+            url: None,
+            favicon: None,
+            tags: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.insert_at(node.clone(), None);
+        Ok(node)
+    }
+
+    /// Переименовывает закладку или папку
+    pub fn rename(&mut self, id: &str, title: &str, now: u64) -> Result<(), LimniError> {
+        let node = self.get_mut(id)?;
+        node.title = title.trim().to_string();
+        node.updated_at = now;
+        Ok(())
+    }
+
+    /// Заменяет теги закладки
+    pub fn set_tags(&mut self, id: &str, tags: Vec<String>, now: u64) -> Result<(), LimniError> {
+        let node = self.get_mut(id)?;
+        node.tags = normalize_tags(tags);
+        node.updated_at = now;
+        Ok(())
+    }
+
+    /// Устанавливает фавиконку для всех закладок с данным URL
+    pub fn set_favicon(&mut self, url: &str, favicon: Option<String>) -> bool {
+        let mut changed = false;
+        for node in self.nodes.iter_mut().filter(|n| n.url.as_deref() == Some(url)) {
+            if node.favicon != favicon {
+                node.favicon = favicon.clone();
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Перемещает узел в другую папку и/или на другую позицию среди соседей
+    pub fn move_node(&mut self, id: &str, parent_id: Option<String>, index: Option<usize>, now: u64) -> Result<(), LimniError> {
+        self.check_parent(parent_id.as_deref())?;
+
+        // Папку нельзя переместить внутрь неё самой или её потомков
+        let mut ancestor = parent_id.clone();
+        while let Some(ancestor_id) = ancestor {
+            if ancestor_id == id {
+                return Err(LimniError::invalid_argument("Cannot move a folder into itself"));
+            }
+            ancestor = self.get(&ancestor_id).and_then(|n| n.parent_id.clone());
+        }
+
+        let position = self.nodes
+            .iter()
+            .position(|n| n.id == id)
+            .ok_or_else(|| LimniError::not_found("Bookmark", id))?;
+        let mut node = self.nodes.remove(position);
+        node.parent_id = parent_id;
+        node.updated_at = now;
+        self.insert_at(node, index);
+        Ok(())
+    }
+
+    /// Удаляет узел; для папки удаляется всё содержимое
+    pub fn remove(&mut self, id: &str) -> Result<usize, LimniError> {
+        if self.get(id).is_none() {
+            return Err(LimniError::not_found("Bookmark", id));
+        }
+
+        let mut to_remove = vec![id.to_string()];
+        let mut i = 0;
+        while i < to_remove.len() {
+            let parent = to_remove[i].clone();
+            to_remove.extend(
+                self.nodes
+                    .iter()
+                    .filter(|n| n.parent_id.as_deref() == Some(parent.as_str()))
+                    .map(|n| n.id.clone()),
+            );
+            i += 1;
+        }
+
+        self.nodes.retain(|n| !to_remove.contains(&n.id));
+        Ok(to_remove.len())
+    }
+
+    /// Ищет закладки по подстроке в title/URL или по точному совпадению тега
+    pub fn search(&self, query: &str) -> Vec<BookmarkNode> {
+        let query = query.trim().to_lowercase();
+        self.nodes
+            .iter()
+            .filter(|n| n.kind == BookmarkKind::Bookmark)
+            .filter(|n| {
+                query.is_empty()
+                    || n.title.to_lowercase().contains(&query)
+                    || n.url.as_deref().is_some_and(|u| u.to_lowercase().contains(&query))
+                    || n.tags.contains(&query)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Приводит теги к нижнему регистру, убирает пустые и дубликаты
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result
+}
+
+/// Состояние закладок, разделяемое между командами и окнами
+#[derive(Default)]
+pub struct BookmarksState {
+    pub store: Mutex<BookmarkStore>,
+}
+
+impl BookmarksState {
+    /// Загружает закладки из директории данных приложения
+    pub fn load(app: &AppHandle) -> Self {
+        let store = match data_dir(app) {
+            Ok(dir) => BookmarkStore::load(dir.join(BOOKMARKS_FILE)),
+            Err(e) => {
+                println!("🦀 Rust: Bookmarks will not be persisted: {}", e);
+                BookmarkStore::default()
+            }
+        };
+
+        Self { store: Mutex::new(store) }
+    }
+}
+
+/// Сохраняет закладки и сообщает всем окнам об изменении
+pub fn save_and_notify(app: &AppHandle, store: &BookmarkStore) -> Result<(), LimniError> {
+    store.save()?;
+    app.emit("bookmarks-changed", store.nodes())
+        .map_err(|e| LimniError::internal(format!("Failed to emit bookmarks change event: {}", e)))
+}
+
+/// Загружает фавиконку закладки в фоне и сохраняет её в дерево
+pub fn fetch_bookmark_favicon(app: &AppHandle, url: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let favicon = match fetch_favicon_backend(&app, url.clone(), false).await {
+            Ok(favicon) => favicon,
+            Err(err) => {
+                println!("🦀 Rust: Failed to fetch bookmark favicon: {}", err);
+                return;
+            }
+        };
+
+        let state = app.state::<BookmarksState>();
+        let mut store = state.store.lock().unwrap();
+        if store.set_favicon(&url, Some(favicon)) {
+            if let Err(e) = save_and_notify(&app, &store) {
+                println!("🦀 Rust: Failed to save bookmarks: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,52 @@
+use super::*;
+
+fn titles(store: &BookmarkStore, parent_id: Option<&str>) -> Vec<String> {
+    store.nodes()
+        .iter()
+        .filter(|n| n.parent_id.as_deref() == parent_id)
+        .map(|n| n.title.clone())
+        .collect()
+}
+
+#[test]
+fn test_add_bookmarks_and_folders() {
+    let mut store = BookmarkStore::default();
+    let folder = store.add_folder(None, "Work", 1).unwrap();
+    store.add_bookmark(Some(folder.id.clone()), "Docs", "https://docs.rs/", vec![" Rust ".into(), "rust".into()], 2).unwrap();
+    store.add_bookmark(None, "Crates", "https://crates.io/", Vec::new(), 3).unwrap();
+
+    assert_eq!(titles(&store, None), vec!["Work", "Crates"]);
+    assert_eq!(titles(&store, Some(&folder.id)), vec!["Docs"]);
+    assert_eq!(store.search("rust")[0].tags, vec!["rust"]);
+    assert!(store.add_bookmark(None, "Bad", "not a url", Vec::new(), 4).is_err());
+}
+
+#[test]
+fn test_move_reorders_and_rejects_cycles() {
+    let mut store = BookmarkStore::default();
+    let a = store.add_folder(None, "A", 1).unwrap();
+    let b = store.add_folder(Some(a.id.clone()), "B", 2).unwrap();
+    let c = store.add_bookmark(None, "C", "https://c.com/", Vec::new(), 3).unwrap();
+
+    store.move_node(&c.id, None, Some(0), 4).unwrap();
+    assert_eq!(titles(&store, None), vec!["C", "A"]);
+
+    store.move_node(&c.id, Some(a.id.clone()), Some(0), 5).unwrap();
+    assert_eq!(titles(&store, Some(&a.id)), vec!["C", "B"]);
+
+    assert!(store.move_node(&a.id, Some(b.id.clone()), None, 6).is_err());
+    assert!(store.move_node(&b.id, Some(c.id.clone()), None, 6).is_err());
+}
+
+#[test]
+fn test_remove_folder_removes_descendants() {
+    let mut store = BookmarkStore::default();
+    let a = store.add_folder(None, "A", 1).unwrap();
+    let b = store.add_folder(Some(a.id.clone()), "B", 2).unwrap();
+    store.add_bookmark(Some(b.id.clone()), "X", "https://x.com/", Vec::new(), 3).unwrap();
+    store.add_bookmark(None, "Y", "https://y.com/", Vec::new(), 4).unwrap();
+
+    assert_eq!(store.remove(&a.id).unwrap(), 3);
+    assert_eq!(titles(&store, None), vec!["Y"]);
+    assert!(store.remove(&a.id).is_err());
+}
@@ -1,41 +1,77 @@
-use tauri::{AppHandle, Manager, Emitter};
-use crate::state::WebviewState;
-use crate::webview::{create_tab_webview_impl, show_tab_webview_impl, hide_all_webviews_impl, close_tab_webview_impl, navigate_webview_impl};
+use tauri::{AppHandle, Manager, Emitter, Window};
+use crate::registry::{registry, tab_webview, TabRecord};
+use crate::error::LimniError;
+use crate::history::{HistoryEntry, HistoryState};
+use crate::bookmarks::{self, BookmarkNode, BookmarksState};
+use crate::session::{self, RestoredTab};
+use crate::settings::{self, BrowserSettings};
+use crate::downloads::{self, DownloadItem, DownloadStatus, DownloadsState};
+use crate::profile::{self, ProfileState, ProfilesInfo};
+use crate::containers::{self, Container, ContainersState};
+use crate::data_store;
+use crate::site_names::{SiteNames, SiteNamesState};
+use crate::i18n::{self, LocaleInfo};
+use crate::favicon::cache::FaviconCacheState;
+use crate::utils::now_millis;
+use tauri_plugin_opener::OpenerExt;
+use crate::webview::{create_tab_webview_impl, show_tab_webview_impl, hide_all_webviews_impl, close_tab_webview_impl, navigate_webview_impl, close_window_webviews_impl, create_new_window_impl, reposition_window_webviews, apply_page_title, set_tab_audio, set_tab_muted, tab_id_for_label};
+use crate::layout::{ChromeInsets, LayoutState};
+use crate::eval::EvalState;
 // Импорт убран, так как fetch_page_title_backend теперь определена как команда Tauri
 use std::time::SystemTime;
 
+/// Проверяет, что команду вызвал интерфейс браузера, а не страница вкладки.
+/// IPC доступен в webview вкладок, поэтому без проверки любой сайт мог бы
+/// читать историю, менять настройки или удалять профили
+fn ensure_browser_ui(webview: &tauri::Webview) -> Result<(), LimniError> {
+    if tab_id_for_label(webview.label()).is_some() {
+        return Err(LimniError::forbidden(webview.label()));
+    }
+    Ok(())
+}
+
 /// Команда для создания нового CHILD webview для вкладки (embedded, ниже UI)
 #[tauri::command]
 pub async fn create_tab_webview(
     app: AppHandle,
+    webview: tauri::Webview,
+    window: Window,
     tab_id: String,
     url: String,
     title: String,
-) -> Result<String, String> {
-    create_tab_webview_impl(app, tab_id, url, title).await
+    container_id: Option<String>,
+) -> Result<String, LimniError> {
+    ensure_browser_ui(&webview)?;
+    create_tab_webview_impl(app, window.label().to_string(), tab_id, url, title, container_id).await
 }
 
 /// Команда для показа webview вкладки
 #[tauri::command]
 pub async fn show_tab_webview(
     app: AppHandle,
+    webview: tauri::Webview,
+    window: Window,
     tab_id: String,
-) -> Result<(), String> {
-    show_tab_webview_impl(app, tab_id).await
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    show_tab_webview_impl(app, window.label().to_string(), tab_id).await
 }
 
-/// Команда для скрытия всех webview'ов (для about:blank вкладок)
+/// Команда для скрытия всех webview'ов окна (для about:blank вкладок)
 #[tauri::command]
-pub async fn hide_all_webviews(app: AppHandle) -> Result<(), String> {
-    hide_all_webviews_impl(app).await
+pub async fn hide_all_webviews(app: AppHandle, webview: tauri::Webview, window: Window) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    hide_all_webviews_impl(app, window.label().to_string()).await
 }
 
 /// Команда для закрытия webview вкладки
 #[tauri::command]
 pub async fn close_tab_webview(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
-) -> Result<(), String> {
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
     close_tab_webview_impl(app, tab_id).await
 }
 
@@ -43,51 +79,76 @@ pub async fn close_tab_webview(
 #[tauri::command]
 pub async fn get_webview_url(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
-) -> Result<String, String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
-    if let Some(webview_label) = webviews.get(&tab_id) {
-        if let Some(webview) = app.get_webview(webview_label) {
-            match webview.url() {
-                Ok(url) => Ok(url.to_string()),
-                Err(e) => Err(format!("Failed to get webview URL: {}", e))
-            }
-        } else {
-            Err("Webview not found".to_string())
-        }
-    } else {
-        Err("Tab not found".to_string())
-    }
+) -> Result<String, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let webview = tab_webview(&app, &tab_id).await?;
+    webview.url()
+        .map(|url| url.to_string())
+        .map_err(|e| LimniError::internal(format!("Failed to get webview URL: {}", e)))
 }
 
-/// Команда для обновления title вкладки из JavaScript injection
+/// IPC команда, которой страница вкладки сообщает свой `document.title`.
+/// Вкладка определяется по webview, из которого пришёл вызов
 #[tauri::command]
 pub async fn update_webview_title(
     app: AppHandle,
-    tab_id: String,
+    webview: tauri::Webview,
     title: String,
-) -> Result<(), String> {
-    println!("🦀 Rust: Received title update: '{}' for tab: {}", title, tab_id);
-    
-    // Отправляем событие об изменении title во frontend
-    app.emit("webview-title-changed", serde_json::json!({
-        "tabId": tab_id,
-        "title": title
-    })).map_err(|e| format!("Failed to emit title change event: {}", e))?;
-    
-    println!("🦀 Rust: Title event emitted successfully");
-    Ok(())
+    url: String,
+) -> Result<(), LimniError> {
+    let tab_id = tab_id_for_label(webview.label())
+        .ok_or_else(|| LimniError::invalid_argument("Title can only be reported by a tab"))?;
+    apply_page_title(&app, tab_id, &url, &title).await
+}
+
+/// IPC команда, которой вкладка возвращает результат скрипта из `eval_in_tab`
+#[tauri::command]
+pub async fn eval_in_tab_callback(
+    app: AppHandle,
+    webview: tauri::Webview,
+    request_id: String,
+    ok: bool,
+    value: Option<serde_json::Value>,
+    error: Option<String>,
+) -> Result<(), LimniError> {
+    let result = if ok {
+        Ok(value.unwrap_or(serde_json::Value::Null))
+    } else {
+        Err(error.unwrap_or_else(|| "Unknown script error".to_string()))
+    };
+    if app.state::<EvalState>().resolve(&request_id, webview.label(), result) {
+        Ok(())
+    } else {
+        Err(LimniError::not_found("Eval request", request_id))
+    }
+}
+
+/// IPC команда, которой страница вкладки сообщает, играет ли в ней звук
+#[tauri::command]
+pub async fn report_tab_audio(app: AppHandle, webview: tauri::Webview, playing: bool) -> Result<(), LimniError> {
+    let tab_id = tab_id_for_label(webview.label())
+        .ok_or_else(|| LimniError::invalid_argument("Audio state can only be reported by a tab"))?;
+    set_tab_audio(&app, tab_id, playing).await
+}
+
+/// Команда для повторной загрузки последнего адреса вкладки со страницы ошибки
+#[tauri::command]
+pub async fn retry_navigation(app: AppHandle, webview: tauri::Webview, tab_id: String) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    crate::page_load::retry_navigation(&app, &tab_id).await
 }
 
 /// Команда для навигации в существующем webview
 #[tauri::command]
 pub async fn navigate_webview(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
     url: String,
-) -> Result<(), String> {
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
     navigate_webview_impl(app, tab_id, url).await
 }
 
@@ -95,9 +156,11 @@ pub async fn navigate_webview(
 #[tauri::command]
 pub async fn navigate_to_home(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
     home_url: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
     // Используем переданный URL или домашнюю страницу по умолчанию
     let url = home_url.unwrap_or_else(|| "https://www.google.com".to_string());
     
@@ -109,62 +172,31 @@ pub async fn navigate_to_home(
 #[tauri::command]
 pub async fn mute_webview(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
-) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
-    if let Some(webview_label) = webviews.get(&tab_id) {
-        if let Some(webview) = app.get_webview(webview_label) {
-            // Выполняем JavaScript для отключения звука всех медиаэлементов
-            let script = get_mute_script();
-            
-            webview.eval(script)
-                .map_err(|e| format!("Failed to mute webview: {}", e))?;
-            
-            println!("🦀 Rust: Webview muted for tab: {}", tab_id);
-            Ok(())
-        } else {
-            Err("Webview not found".to_string())
-        }
-    } else {
-        Err("Tab not found".to_string())
-    }
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    set_tab_muted(&app, &tab_id, true).await
 }
 
 /// Команда для включения звука webview
 #[tauri::command]
 pub async fn unmute_webview(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
-) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
-    if let Some(webview_label) = webviews.get(&tab_id) {
-        if let Some(webview) = app.get_webview(webview_label) {
-            // Выполняем JavaScript для включения звука всех медиаэлементов
-            let script = get_unmute_script();
-            
-            webview.eval(script)
-                .map_err(|e| format!("Failed to unmute webview: {}", e))?;
-            
-            println!("🦀 Rust: Webview unmuted for tab: {}", tab_id);
-            Ok(())
-        } else {
-            Err("Webview not found".to_string())
-        }
-    } else {
-        Err("Tab not found".to_string())
-    }
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    set_tab_muted(&app, &tab_id, false).await
 }
 
 /// Команда для открытия URL в новой вкладке (альтернатива opener plugin)
 #[tauri::command]
 pub async fn open_url_in_new_tab(
     app: AppHandle,
+    window: Window,
     url: String,
-) -> Result<String, String> {
+) -> Result<String, LimniError> {
     // Генерируем ID для новой вкладки
     let timestamp = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -172,165 +204,634 @@ pub async fn open_url_in_new_tab(
         .unwrap_or(0);
     let tab_id = format!("tab_{}", timestamp);
     
-    // Создаем новый webview для этого URL
-    let webview_label = create_tab_webview_impl(app.clone(), tab_id.clone(), url.clone(), "Загрузка...".to_string()).await?;
+    // Создаем новый webview для этого URL в том же окне, откуда пришёл запрос,
+    // в контейнере, закреплённом за сайтом
+    let window_label = window.label().to_string();
+    let container_id = containers::container_for_url(&app, &url);
+    let loading_title = i18n::t(&app, "tab.loading");
+    let webview_label = create_tab_webview_impl(app.clone(), window_label.clone(), tab_id.clone(), url.clone(), loading_title.clone(), container_id).await?;
     
-    // Отправляем событие о создании новой вкладки во frontend
-    app.emit("new-tab-created", serde_json::json!({
+    // Отправляем событие о создании новой вкладки во frontend этого окна
+    app.emit_to(window_label.as_str(), "new-tab-created", serde_json::json!({
         "tabId": tab_id,
         "url": url,
-        "title": "Загрузка...",
-        "webviewLabel": webview_label
-    })).map_err(|e| format!("Failed to emit new tab event: {}", e))?;
+        "title": loading_title,
+        "webviewLabel": webview_label,
+        "windowLabel": window_label,
+        "containerId": registry(&app).get(&tab_id).await.and_then(|tab| tab.container_id)
+    })).map_err(|e| LimniError::internal(format!("Failed to emit new tab event: {}", e)))?;
     
     Ok(tab_id)
 }
 
+/// Команда для получения вкладок окна, из которого она вызвана, со всеми их данными
+#[tauri::command]
+pub async fn get_tabs(app: AppHandle, webview: tauri::Webview, window: Window) -> Result<Vec<TabRecord>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    Ok(registry(&app).in_window(window.label()).await)
+}
+
+/// Команда, которой frontend сообщает отступы своего интерфейса от краёв окна.
+/// Webview'ы вкладок окна переставляются в оставшуюся область
+#[tauri::command]
+pub async fn set_chrome_insets(app: AppHandle, webview: tauri::Webview, window: Window, insets: ChromeInsets) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    insets.validate()?;
+    if app.state::<LayoutState>().set_insets(window.label(), insets) {
+        reposition_window_webviews(&app, window.label()).await;
+    }
+    Ok(())
+}
+
 /// Команда для получения информации о webview'ах (для отладки)
 #[tauri::command]
-pub async fn get_webview_info(app: AppHandle) -> Result<Vec<String>, String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
+pub async fn get_webview_info(app: AppHandle, webview: tauri::Webview) -> Result<Vec<String>, LimniError> {
+    ensure_browser_ui(&webview)?;
     let mut info = Vec::new();
-    for (tab_id, webview_label) in webviews.iter() {
-        let exists = app.get_webview(webview_label).is_some();
-        info.push(format!("Tab: {}, Label: {}, Exists: {}", tab_id, webview_label, exists));
+    for tab in registry(&app).all().await {
+        let exists = app.get_webview(&tab.webview_label).is_some();
+        info.push(format!("Tab: {}, Label: {}, Exists: {}", tab.tab_id, tab.webview_label, exists));
     }
     
     Ok(info)
 }
 
-/// Команда для получения фавиконки через бэкенд
+/// Команда для получения фавиконки через бэкенд.
+/// Значки для приватных окон не записываются в кэш и не запрашиваются у внешнего сервиса
 #[tauri::command]
-pub async fn fetch_favicon_backend(url: String) -> Result<String, String> {
-    crate::utils::fetch_favicon_backend(url).await
+pub async fn fetch_favicon_backend(app: AppHandle, webview: tauri::Webview, window: Window, url: String) -> Result<String, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let private = crate::private::is_private_window(window.label());
+    crate::favicon::fetch_favicon_backend(&app, url, private).await
+}
+
+/// Значок сайта из кэша без обращения к сети (для истории и закладок)
+#[tauri::command]
+pub fn get_cached_favicon(app: AppHandle, webview: tauri::Webview, url: String) -> Result<Option<String>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    Ok(crate::favicon::cached_favicon(&app, &url))
+}
+
+/// Очищает кэш значков
+#[tauri::command]
+pub fn clear_favicon_cache(app: AppHandle, webview: tauri::Webview) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    app.state::<FaviconCacheState>().clear()?;
+    println!("🦀 Rust: Favicon cache cleared");
+    Ok(())
+}
+
+/// Метаданные страницы во вкладке: описание, canonical, Open Graph, theme-color, ленты
+#[tauri::command]
+pub async fn get_page_metadata(app: AppHandle, webview: tauri::Webview, tab_id: String) -> Result<crate::utils::PageMetadata, LimniError> {
+    ensure_browser_ui(&webview)?;
+    crate::utils::get_page_metadata(&app, &tab_id).await
 }
 
 /// Команда для получения заголовка страницы через бэкенд
 #[tauri::command]
-pub async fn fetch_page_title_backend(url: String) -> Result<String, String> {
-    crate::utils::fetch_page_title_backend(url).await
+pub async fn fetch_page_title_backend(app: AppHandle, webview: tauri::Webview, url: String) -> Result<String, LimniError> {
+    ensure_browser_ui(&webview)?;
+    crate::utils::fetch_page_title_backend(&app, url).await
 }
 
 /// Команда для перезагрузки вкладки
 #[tauri::command]
 pub async fn reload_tab(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
-) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
-    if let Some(webview_label) = webviews.get(&tab_id) {
-        if let Some(webview) = app.get_webview(webview_label) {
-            webview.eval("window.location.reload()")
-                .map_err(|e| format!("Failed to reload webview: {}", e))?;
-            Ok(())
-        } else {
-            Err("Webview not found".to_string())
-        }
-    } else {
-        Err("Tab not found".to_string())
-    }
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let webview = tab_webview(&app, &tab_id).await?;
+    webview.eval("window.location.reload()")
+        .map_err(|e| LimniError::internal(format!("Failed to reload webview: {}", e)))
 }
 
 /// Команда для навигации назад
 #[tauri::command]
 pub async fn navigate_back(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
-) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
-    if let Some(webview_label) = webviews.get(&tab_id) {
-        if let Some(webview) = app.get_webview(webview_label) {
-            webview.eval("window.history.back()")
-                .map_err(|e| format!("Failed to navigate back: {}", e))?;
-            Ok(())
-        } else {
-            Err("Webview not found".to_string())
-        }
-    } else {
-        Err("Tab not found".to_string())
-    }
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let webview = tab_webview(&app, &tab_id).await?;
+    webview.eval("window.history.back()")
+        .map_err(|e| LimniError::internal(format!("Failed to navigate back: {}", e)))
 }
 
 /// Команда для навигации вперед
 #[tauri::command]
 pub async fn navigate_forward(
     app: AppHandle,
+    webview: tauri::Webview,
     tab_id: String,
-) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let webview = tab_webview(&app, &tab_id).await?;
+    webview.eval("window.history.forward()")
+        .map_err(|e| LimniError::internal(format!("Failed to navigate forward: {}", e)))
+}
+
+/// Команда для получения истории посещений (от новых к старым)
+#[tauri::command]
+pub async fn get_history(
+    app: AppHandle,
+    webview: tauri::Webview,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<HistoryState>();
+    let store = state.store.lock().unwrap();
+    Ok(store.list(offset.unwrap_or(0), limit.unwrap_or(100)))
+}
+
+/// Команда для поиска по истории (по URL и title)
+#[tauri::command]
+pub async fn search_history(
+    app: AppHandle,
+    webview: tauri::Webview,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<HistoryState>();
+    let store = state.store.lock().unwrap();
+    Ok(store.search(&query, limit.unwrap_or(100)))
+}
+
+/// Команда для удаления одной записи истории
+#[tauri::command]
+pub async fn delete_history_entry(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<HistoryState>();
+    let mut store = state.store.lock().unwrap();
     
-    if let Some(webview_label) = webviews.get(&tab_id) {
-        if let Some(webview) = app.get_webview(webview_label) {
-            webview.eval("window.history.forward()")
-                .map_err(|e| format!("Failed to navigate forward: {}", e))?;
-            Ok(())
-        } else {
-            Err("Webview not found".to_string())
-        }
+    if store.remove(&id) {
+        store.save()
     } else {
-        Err("Tab not found".to_string())
+        Err(LimniError::not_found("History entry", id))
     }
 }
 
-/// Возвращает скрипт для отключения звука
-fn get_mute_script() -> &'static str {
-    r#"
-        (function() {
-            // Отключаем звук всех audio элементов
-            document.querySelectorAll('audio').forEach(audio => {
-                audio.muted = true;
-            });
-            
-            // Отключаем звук всех video элементов
-            document.querySelectorAll('video').forEach(video => {
-                video.muted = true;
-            });
-            
-            // Для YouTube и других сайтов с iframe
-            document.querySelectorAll('iframe').forEach(iframe => {
-                try {
-                    iframe.contentWindow.postMessage('{"event":"command","func":"mute","args":[]}', '*');
-                } catch(e) {}
-            });
-            
-            console.log('🔇 Tab muted');
-        })();
-    "#
-}
-
-/// Возвращает скрипт для включения звука
-fn get_unmute_script() -> &'static str {
-    r#"
-        (function() {
-            // Включаем звук всех audio элементов
-            document.querySelectorAll('audio').forEach(audio => {
-                audio.muted = false;
-            });
-            
-            // Включаем звук всех video элементов
-            document.querySelectorAll('video').forEach(video => {
-                video.muted = false;
-            });
-            
-            // Для YouTube и других сайтов с iframe
-            document.querySelectorAll('iframe').forEach(iframe => {
-                try {
-                    iframe.contentWindow.postMessage('{"event":"command","func":"unMute","args":[]}', '*');
-                } catch(e) {}
-            });
-            
-            console.log('🔊 Tab unmuted');
-        })();
-    "#
+/// Команда для очистки истории за промежуток времени (мс с начала эпохи).
+/// Без границ очищает всю историю. Возвращает количество удалённых записей
+#[tauri::command]
+pub async fn clear_history(
+    app: AppHandle,
+    webview: tauri::Webview,
+    from: Option<u64>,
+    to: Option<u64>,
+) -> Result<usize, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<HistoryState>();
+    let mut store = state.store.lock().unwrap();
+    
+    let removed = store.clear_range(from, to);
+    store.save()?;
+    Ok(removed)
+}
+
+/// Команда для получения всех закладок и папок (в порядке отображения)
+#[tauri::command]
+pub async fn get_bookmarks(app: AppHandle, webview: tauri::Webview) -> Result<Vec<BookmarkNode>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<BookmarksState>();
+    let store = state.store.lock().unwrap();
+    Ok(store.nodes().to_vec())
+}
+
+/// Команда для поиска закладок по title, URL или тегу
+#[tauri::command]
+pub async fn search_bookmarks(
+    app: AppHandle,
+    webview: tauri::Webview,
+    query: String,
+) -> Result<Vec<BookmarkNode>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<BookmarksState>();
+    let store = state.store.lock().unwrap();
+    Ok(store.search(&query))
+}
+
+/// Команда для добавления закладки (фавиконка подгружается в фоне)
+#[tauri::command]
+pub async fn add_bookmark(
+    app: AppHandle,
+    webview: tauri::Webview,
+    url: String,
+    title: String,
+    parent_id: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<BookmarkNode, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let node = {
+        let state = app.state::<BookmarksState>();
+        let mut store = state.store.lock().unwrap();
+        let node = store.add_bookmark(parent_id, &title, &url, tags.unwrap_or_default(), now_millis())?;
+        bookmarks::save_and_notify(&app, &store)?;
+        node
+    };
+    
+    if url.starts_with("http") {
+        bookmarks::fetch_bookmark_favicon(&app, url);
+    }
+    
+    Ok(node)
+}
+
+/// Команда для создания папки закладок
+#[tauri::command]
+pub async fn create_bookmark_folder(
+    app: AppHandle,
+    webview: tauri::Webview,
+    title: String,
+    parent_id: Option<String>,
+) -> Result<BookmarkNode, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<BookmarksState>();
+    let mut store = state.store.lock().unwrap();
+    
+    let node = store.add_folder(parent_id, &title, now_millis())?;
+    bookmarks::save_and_notify(&app, &store)?;
+    Ok(node)
+}
+
+/// Команда для переименования закладки или папки
+#[tauri::command]
+pub async fn rename_bookmark(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+    title: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<BookmarksState>();
+    let mut store = state.store.lock().unwrap();
+    
+    store.rename(&id, &title, now_millis())?;
+    bookmarks::save_and_notify(&app, &store)
+}
+
+/// Команда для изменения тегов закладки
+#[tauri::command]
+pub async fn set_bookmark_tags(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+    tags: Vec<String>,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<BookmarksState>();
+    let mut store = state.store.lock().unwrap();
+    
+    store.set_tags(&id, tags, now_millis())?;
+    bookmarks::save_and_notify(&app, &store)
+}
+
+/// Команда для перемещения закладки или папки.
+/// `parent_id == None` — корневой уровень, `index == None` — в конец папки
+#[tauri::command]
+pub async fn move_bookmark(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+    parent_id: Option<String>,
+    index: Option<usize>,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<BookmarksState>();
+    let mut store = state.store.lock().unwrap();
+    
+    store.move_node(&id, parent_id, index, now_millis())?;
+    bookmarks::save_and_notify(&app, &store)
+}
+
+/// Команда для удаления закладки или папки вместе с содержимым
+#[tauri::command]
+pub async fn delete_bookmark(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<BookmarksState>();
+    let mut store = state.store.lock().unwrap();
+    
+    store.remove(&id)?;
+    bookmarks::save_and_notify(&app, &store)
+}
+
+/// Команда для восстановления вкладок прошлого запуска.
+/// Webview сразу создаётся только для активной вкладки, остальные — при первом показе
+#[tauri::command]
+pub async fn restore_session(app: AppHandle, webview: tauri::Webview, window: Window) -> Result<Vec<RestoredTab>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    session::restore_session_impl(app, window.label().to_string()).await
+}
+
+/// Команда для закрепления/открепления вкладки в снимке сессии
+#[tauri::command]
+pub async fn set_tab_pinned(
+    app: AppHandle,
+    webview: tauri::Webview,
+    tab_id: String,
+    pinned: bool,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    session::update(&app, |s| s.set_pinned(&tab_id, pinned));
+    Ok(())
+}
+
+/// Команда для сохранения порядка вкладок в снимке сессии
+#[tauri::command]
+pub async fn reorder_tabs(
+    app: AppHandle,
+    webview: tauri::Webview,
+    tab_ids: Vec<String>,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    session::update(&app, |s| s.reorder(&tab_ids));
+    Ok(())
+}
+
+/// Команда для открытия нового окна браузера
+#[tauri::command]
+pub async fn create_new_window(app: AppHandle, webview: tauri::Webview) -> Result<String, LimniError> {
+    ensure_browser_ui(&webview)?;
+    create_new_window_impl(app, false).await
+}
+
+/// Команда для открытия приватного окна: временное хранилище данных,
+/// без записи истории, сессии и списка загрузок
+#[tauri::command]
+pub async fn create_private_window(app: AppHandle, webview: tauri::Webview) -> Result<String, LimniError> {
+    ensure_browser_ui(&webview)?;
+    create_new_window_impl(app, true).await
+}
+
+/// Команда для проверки, является ли окно, из которого она вызвана, приватным
+#[tauri::command]
+pub async fn is_private_window(webview: tauri::Webview, window: Window) -> Result<bool, LimniError> {
+    ensure_browser_ui(&webview)?;
+    Ok(crate::private::is_private_window(window.label()))
+}
+
+/// Команда для закрытия окна, из которого она вызвана, вместе с его вкладками
+#[tauri::command]
+pub async fn close_current_window(app: AppHandle, webview: tauri::Webview, window: Window) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    close_window_webviews_impl(app, window.label().to_string()).await?;
+    window.close().map_err(|e| LimniError::internal(format!("Failed to close window: {}", e)))
+}
+
+/// Команда для получения настроек браузера
+#[tauri::command]
+pub async fn get_settings(app: AppHandle, webview: tauri::Webview) -> Result<BrowserSettings, LimniError> {
+    ensure_browser_ui(&webview)?;
+    Ok(settings::current(&app))
+}
+
+/// Команда для сохранения настроек браузера
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    webview: tauri::Webview,
+    settings: BrowserSettings,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    settings::update_settings_impl(&app, settings)
+}
+
+/// Команда для получения списка загрузок (от новых к старым).
+/// Загрузки приватного окна видны только в нём
+#[tauri::command]
+pub async fn get_downloads(app: AppHandle, webview: tauri::Webview, window: Window) -> Result<Vec<DownloadItem>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<DownloadsState>();
+    let items = state.items.lock().unwrap();
+    Ok(items.iter().filter(|item| item.visible_in(window.label())).cloned().collect())
+}
+
+/// Команда для отмены загрузки
+#[tauri::command]
+pub async fn cancel_download(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    downloads::cancel_download_impl(&app, &id)
+}
+
+/// Команда для повтора неудачной или отменённой загрузки
+#[tauri::command]
+pub async fn retry_download(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    downloads::retry_download_impl(&app, &id)
+}
+
+/// Команда для открытия папки со скачанным файлом
+#[tauri::command]
+pub async fn open_download_folder(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let path = {
+        let state = app.state::<DownloadsState>();
+        let items = state.items.lock().unwrap();
+        let item = items.iter().find(|i| i.id == id)
+            .ok_or_else(|| LimniError::not_found("Download", &id))?;
+        item.path.clone().ok_or_else(|| LimniError::invalid_argument("Download has no file yet"))?
+    };
+    
+    app.opener()
+        .reveal_item_in_dir(&path)
+        .map_err(|e| LimniError::internal(format!("Failed to open download folder: {}", e)))
+}
+
+/// Команда для очистки списка от завершённых загрузок окна (файлы не удаляются)
+#[tauri::command]
+pub async fn clear_downloads(app: AppHandle, webview: tauri::Webview, window: Window) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<DownloadsState>();
+    state.items.lock().unwrap().retain(|i| i.status == DownloadStatus::InProgress || !i.visible_in(window.label()));
+    state.save()
+}
+
+/// Команда для получения списка профилей и текущего профиля
+#[tauri::command]
+pub async fn get_profiles(app: AppHandle, webview: tauri::Webview) -> Result<ProfilesInfo, LimniError> {
+    ensure_browser_ui(&webview)?;
+    Ok(app.state::<ProfileState>().info())
+}
+
+/// Команда для создания профиля с отдельными данными
+#[tauri::command]
+pub async fn create_profile(
+    app: AppHandle,
+    webview: tauri::Webview,
+    name: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    app.state::<ProfileState>().create(&name)
+}
+
+/// Команда для удаления профиля вместе с его данными
+#[tauri::command]
+pub async fn delete_profile(
+    app: AppHandle,
+    webview: tauri::Webview,
+    name: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<ProfileState>();
+    let webview_dir = state.webview_data_dir_of(&name);
+    state.delete(&name)?;
+    if let Some(dir) = webview_dir {
+        data_store::remove_storage(&app, &dir);
+    }
+    Ok(())
+}
+
+/// Команда для перезапуска браузера в другом профиле
+#[tauri::command]
+pub async fn switch_profile(
+    app: AppHandle,
+    webview: tauri::Webview,
+    name: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    profile::switch_profile_impl(&app, &name)
+}
+
+/// Команда для получения списка контейнеров
+#[tauri::command]
+pub async fn get_containers(app: AppHandle, webview: tauri::Webview) -> Result<Vec<Container>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<ContainersState>();
+    let store = state.store.lock().unwrap();
+    Ok(store.containers().to_vec())
+}
+
+/// Команда для создания контейнера
+#[tauri::command]
+pub async fn create_container(
+    app: AppHandle,
+    webview: tauri::Webview,
+    name: String,
+    color: String,
+) -> Result<Container, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<ContainersState>();
+    let mut store = state.store.lock().unwrap();
+    let container = store.add(&name, &color, now_millis())?;
+    containers::save_and_notify(&app, &store)?;
+    Ok(container)
+}
+
+/// Команда для переименования контейнера и смены его цвета
+#[tauri::command]
+pub async fn update_container(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+    name: String,
+    color: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<ContainersState>();
+    let mut store = state.store.lock().unwrap();
+    store.update(&id, &name, &color)?;
+    containers::save_and_notify(&app, &store)
+}
+
+/// Команда для удаления контейнера вместе с его cookies и данными сайтов
+#[tauri::command]
+pub async fn delete_container(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    containers::delete_container_impl(&app, &id).await
+}
+
+/// Команда для закрепления сайта за контейнером (`containerId: null` снимает закрепление)
+#[tauri::command]
+pub async fn assign_site_container(
+    app: AppHandle,
+    webview: tauri::Webview,
+    url: String,
+    container_id: Option<String>,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<ContainersState>();
+    let mut store = state.store.lock().unwrap();
+    store.assign_site(&url, container_id.as_deref())?;
+    containers::save_and_notify(&app, &store)
+}
+
+/// Команда для получения контейнера вкладки (`null` — вкладка без контейнера)
+#[tauri::command]
+pub async fn get_tab_container(
+    app: AppHandle,
+    webview: tauri::Webview,
+    tab_id: String,
+) -> Result<Option<Container>, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let Some(container_id) = registry(&app).get(&tab_id).await.and_then(|tab| tab.container_id) else {
+        return Ok(None);
+    };
+    let state = app.state::<ContainersState>();
+    let store = state.store.lock().unwrap();
+    Ok(store.get(&container_id).cloned())
+}
+
+/// Команда для получения языка интерфейса и списка доступных языков
+#[tauri::command]
+pub async fn get_locale(app: AppHandle, webview: tauri::Webview) -> Result<LocaleInfo, LimniError> {
+    ensure_browser_ui(&webview)?;
+    Ok(i18n::locale_info(&app))
+}
+
+/// Команда для получения встроенных и пользовательских названий сайтов
+#[tauri::command]
+pub async fn get_site_names(app: AppHandle, webview: tauri::Webview) -> Result<SiteNames, LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<SiteNamesState>();
+    let store = state.store.lock().unwrap();
+    Ok(store.names())
+}
+
+/// Команда для задания названия сайта (`name: null` удаляет пользовательское название)
+#[tauri::command]
+pub async fn set_site_name(
+    app: AppHandle,
+    webview: tauri::Webview,
+    domain: String,
+    name: Option<String>,
+) -> Result<(), LimniError> {
+    ensure_browser_ui(&webview)?;
+    let state = app.state::<SiteNamesState>();
+    let mut store = state.store.lock().unwrap();
+    store.set(&domain, name.as_deref())?;
+    store.save()
 }
 
 // Подключаем модуль тестов
 #[cfg(test)]
+#[allow(clippy::module_inception, clippy::assertions_on_constants)]
 mod tests;
\ No newline at end of file
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::error::LimniError;
+use crate::storage::{data_dir, read_json, write_json_atomic};
+
+/// Имя файла контейнеров в директории данных профиля
+const CONTAINERS_FILE: &str = "containers.json";
+
+/// Директория с хранилищами webview контейнеров
+const CONTAINERS_DIR: &str = "containers";
+
+/// Допустимые цвета контейнеров (соответствуют цветам меток во frontend)
+pub const CONTAINER_COLORS: &[&str] = &[
+    "blue", "turquoise", "green", "yellow", "orange", "red", "pink", "purple",
+];
+
+/// Контейнер — отдельное хранилище cookies и данных сайтов внутри профиля
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Container {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub created_at: u64,
+}
+
+/// Формат файла контейнеров на диске
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContainersFile {
+    containers: Vec<Container>,
+    site_assignments: BTreeMap<String, String>,
+}
+
+/// Реестр контейнеров и закреплённых за ними сайтов
+#[derive(Default)]
+pub struct ContainerStore {
+    path: Option<PathBuf>,
+    containers: Vec<Container>,
+    /// Хост сайта (без `www.`) -> id контейнера
+    site_assignments: BTreeMap<String, String>,
+    next_id: u64,
+}
+
+/// Ключ сайта для закрепления: хост в нижнем регистре без `www.`
+pub fn site_key(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").map(str::to_string).unwrap_or(host))
+}
+
+fn validate_color(color: &str) -> Result<(), LimniError> {
+    if CONTAINER_COLORS.contains(&color) {
+        Ok(())
+    } else {
+        Err(LimniError::invalid_argument(format!("Unknown container color: {}", color)))
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, LimniError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(LimniError::invalid_argument("Container name cannot be empty"));
+    }
+    Ok(name.to_string())
+}
+
+impl ContainerStore {
+    /// Загружает реестр из файла (пустой, если файла нет или он повреждён)
+    pub fn load(path: PathBuf) -> Self {
+        let file: ContainersFile = read_json(&path).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load containers, starting empty: {}", e);
+            ContainersFile::default()
+        });
+
+        Self {
+            path: Some(path),
+            containers: file.containers,
+            site_assignments: file.site_assignments,
+            next_id: 0,
+        }
+    }
+
+    /// Сохраняет реестр на диск
+    pub fn save(&self) -> Result<(), LimniError> {
+        match &self.path {
+            Some(path) => write_json_atomic(path, &ContainersFile {
+                containers: self.containers.clone(),
+                site_assignments: self.site_assignments.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn containers(&self) -> &[Container] {
+        &self.containers
+    }
+
+    pub fn site_assignments(&self) -> &BTreeMap<String, String> {
+        &self.site_assignments
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Container> {
+        self.containers.iter().find(|c| c.id == id)
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut Container, LimniError> {
+        self.containers.iter_mut().find(|c| c.id == id).ok_or_else(|| LimniError::not_found("Container", id))
+    }
+
+    pub fn add(&mut self, name: &str, color: &str, now: u64) -> Result<Container, LimniError> {
+        let name = validate_name(name)?;
+        validate_color(color)?;
+
+        self.next_id += 1;
+        let container = Container {
+            id: format!("ct_{}_{}", now, self.next_id),
+            name,
+            color: color.to_string(),
+            created_at: now,
+        };
+        self.containers.push(container.clone());
+        Ok(container)
+    }
+
+    pub fn update(&mut self, id: &str, name: &str, color: &str) -> Result<(), LimniError> {
+        let name = validate_name(name)?;
+        validate_color(color)?;
+        let container = self.get_mut(id)?;
+        container.name = name;
+        container.color = color.to_string();
+        Ok(())
+    }
+
+    /// Удаляет контейнер вместе с закреплёнными за ним сайтами
+    pub fn remove(&mut self, id: &str) -> Result<(), LimniError> {
+        let before = self.containers.len();
+        self.containers.retain(|c| c.id != id);
+        if self.containers.len() == before {
+            return Err(LimniError::not_found("Container", id));
+        }
+        self.site_assignments.retain(|_, container_id| container_id != id);
+        Ok(())
+    }
+
+    /// Закрепляет сайт за контейнером (`None` — открывать без контейнера)
+    pub fn assign_site(&mut self, url: &str, container_id: Option<&str>) -> Result<(), LimniError> {
+        let site = site_key(url).ok_or_else(|| LimniError::invalid_url(url, "no host"))?;
+        match container_id {
+            Some(id) => {
+                if self.get(id).is_none() {
+                    return Err(LimniError::not_found("Container", id));
+                }
+                self.site_assignments.insert(site, id.to_string());
+            }
+            None => {
+                self.site_assignments.remove(&site);
+            }
+        }
+        Ok(())
+    }
+
+    /// Контейнер, закреплённый за сайтом. Если за самим хостом ничего не закреплено,
+    /// проверяются родительские домены (`mail.example.com` -> `example.com`)
+    pub fn container_for_url(&self, url: &str) -> Option<&Container> {
+        let site = site_key(url)?;
+        let mut host = site.as_str();
+        loop {
+            if let Some(id) = self.site_assignments.get(host) {
+                return self.get(id);
+            }
+            host = host.split_once('.')?.1;
+        }
+    }
+}
+
+/// Состояние контейнеров, разделяемое между командами и окнами
+#[derive(Default)]
+pub struct ContainersState {
+    pub store: Mutex<ContainerStore>,
+}
+
+impl ContainersState {
+    /// Загружает контейнеры из директории данных профиля
+    pub fn load(app: &AppHandle) -> Self {
+        let store = match data_dir(app) {
+            Ok(dir) => ContainerStore::load(dir.join(CONTAINERS_FILE)),
+            Err(e) => {
+                println!("🦀 Rust: Containers will not be persisted: {}", e);
+                ContainerStore::default()
+            }
+        };
+
+        Self { store: Mutex::new(store) }
+    }
+}
+
+/// Директория данных webview контейнера
+fn container_dir(app: &AppHandle, container_id: &str) -> Result<PathBuf, LimniError> {
+    Ok(data_dir(app)?.join(CONTAINERS_DIR).join(container_id))
+}
+
+/// Проверяет, что контейнер существует, и возвращает директорию его данных webview
+pub fn container_data_dir(app: &AppHandle, container_id: &str) -> Result<PathBuf, LimniError> {
+    let exists = app.try_state::<ContainersState>()
+        .is_some_and(|state| state.store.lock().unwrap().get(container_id).is_some());
+    if !exists {
+        return Err(LimniError::not_found("Container", container_id));
+    }
+    crate::data_store::ensure_separate_storage()?;
+
+    let dir = container_dir(app, container_id)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| LimniError::io(format!("Failed to create container data dir: {}", e)))?;
+    Ok(dir)
+}
+
+/// Id контейнера, закреплённого за сайтом
+pub fn container_for_url(app: &AppHandle, url: &str) -> Option<String> {
+    let state = app.try_state::<ContainersState>()?;
+    let store = state.store.lock().unwrap();
+    store.container_for_url(url).map(|c| c.id.clone())
+}
+
+/// Сохраняет реестр и сообщает всем окнам об изменении
+pub fn save_and_notify(app: &AppHandle, store: &ContainerStore) -> Result<(), LimniError> {
+    store.save()?;
+    app.emit("containers-changed", serde_json::json!({
+        "containers": store.containers(),
+        "siteAssignments": store.site_assignments()
+    })).map_err(|e| LimniError::internal(format!("Failed to emit containers change event: {}", e)))
+}
+
+/// Удаляет контейнер и его данные. Контейнер с открытыми вкладками удалить нельзя
+pub async fn delete_container_impl(app: &AppHandle, container_id: &str) -> Result<(), LimniError> {
+    let in_use = crate::registry::registry(app).all().await
+        .iter()
+        .any(|tab| tab.container_id.as_deref() == Some(container_id));
+    if in_use {
+        return Err(LimniError::invalid_argument("Container has open tabs"));
+    }
+
+    {
+        let state = app.state::<ContainersState>();
+        let mut store = state.store.lock().unwrap();
+        store.remove(container_id)?;
+        save_and_notify(app, &store)?;
+    }
+
+    let dir = container_dir(app, container_id)?;
+    crate::data_store::remove_storage(app, &dir);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| LimniError::io(format!("Failed to delete container data: {}", e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,41 @@
+use super::*;
+
+#[test]
+fn test_add_and_update_container() {
+    let mut store = ContainerStore::default();
+    let work = store.add(" Work ", "blue", 1).unwrap();
+    assert_eq!(work.name, "Work");
+    assert!(store.add("", "blue", 2).is_err());
+    assert!(store.add("Bank", "black", 3).is_err());
+
+    store.update(&work.id, "Office", "green").unwrap();
+    assert_eq!(store.get(&work.id).unwrap().color, "green");
+    assert!(store.update("missing", "X", "red").is_err());
+}
+
+#[test]
+fn test_site_assignment_matches_subdomains() {
+    let mut store = ContainerStore::default();
+    let work = store.add("Work", "blue", 1).unwrap();
+    store.assign_site("https://www.example.com/login", Some(&work.id)).unwrap();
+
+    assert_eq!(store.container_for_url("https://example.com/").unwrap().id, work.id);
+    assert_eq!(store.container_for_url("https://mail.example.com/inbox").unwrap().id, work.id);
+    assert!(store.container_for_url("https://example.org/").is_none());
+    assert!(store.assign_site("https://example.org/", Some("missing")).is_err());
+
+    store.assign_site("https://example.com/", None).unwrap();
+    assert!(store.container_for_url("https://example.com/").is_none());
+}
+
+#[test]
+fn test_remove_container_drops_assignments() {
+    let mut store = ContainerStore::default();
+    let personal = store.add("Personal", "pink", 1).unwrap();
+    store.assign_site("https://social.example/", Some(&personal.id)).unwrap();
+
+    store.remove(&personal.id).unwrap();
+    assert!(store.containers().is_empty());
+    assert!(store.site_assignments().is_empty());
+    assert!(store.remove(&personal.id).is_err());
+}
@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
+use std::sync::LazyLock;
+use tauri::{AppHandle, Runtime, WebviewBuilder};
+use crate::error::LimniError;
+
+/// Отдельные хранилища WKWebView (`dataStoreForIdentifier`) появились в macOS 14
+#[cfg(target_os = "macos")]
+const MIN_MACOS_MAJOR: u32 = 14;
+
+/// Старшая версия macOS, `None` — определить не удалось
+#[cfg(target_os = "macos")]
+static MACOS_MAJOR: LazyLock<Option<u32>> = LazyLock::new(|| {
+    let output = std::process::Command::new("/usr/bin/sw_vers")
+        .arg("-productVersion")
+        .output()
+        .ok()?;
+    parse_major_version(&String::from_utf8_lossy(&output.stdout))
+});
+
+/// Старшая версия из `sw_vers -productVersion`: `14.2.1` -> 14
+#[cfg(any(target_os = "macos", test))]
+fn parse_major_version(version: &str) -> Option<u32> {
+    version.trim().split('.').next()?.parse().ok()
+}
+
+/// Идентификатор хранилища WKWebView для директории данных: первые 16 байт SHA-256 пути.
+/// Одна и та же директория между запусками получает одно и то же хранилище
+#[cfg(any(target_os = "macos", test))]
+fn store_id(dir: &Path) -> [u8; 16] {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(dir.to_string_lossy().as_bytes());
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&digest[..16]);
+    id
+}
+
+/// Проверяет, что webview можно дать хранилище отдельно от общего хранилища приложения.
+/// На macOS до 14 WebKit молча подставил бы общее хранилище, поэтому отказываем явно
+pub fn ensure_separate_storage() -> Result<(), LimniError> {
+    #[cfg(target_os = "macos")]
+    if MACOS_MAJOR.is_some_and(|major| major < MIN_MACOS_MAJOR) {
+        return Err(LimniError::invalid_argument(format!(
+            "Separate browsing data requires macOS {} or newer",
+            MIN_MACOS_MAJOR
+        )));
+    }
+    Ok(())
+}
+
+/// Хранит данные webview (cookies, localStorage, кэш) в директории `dir`.
+/// WKWebView не умеет хранить данные в произвольной директории, поэтому на macOS
+/// хранилище выбирается по идентификатору, вычисленному из пути
+pub fn with_storage<R: Runtime>(builder: WebviewBuilder<R>, dir: PathBuf) -> Result<WebviewBuilder<R>, LimniError> {
+    ensure_separate_storage()?;
+    #[cfg(target_os = "macos")]
+    let builder = builder.data_store_identifier(store_id(&dir));
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.data_directory(dir);
+    Ok(builder)
+}
+
+/// Хранилище вкладки приватного окна. Там, где отдельных хранилищ нет, вкладка получает
+/// собственное хранилище в памяти: данные не сохраняются, но не делятся между вкладками окна
+pub fn with_private_storage<R: Runtime>(builder: WebviewBuilder<R>, dir: PathBuf) -> Result<WebviewBuilder<R>, LimniError> {
+    if ensure_separate_storage().is_err() {
+        return Ok(builder.incognito(true));
+    }
+    with_storage(builder, dir)
+}
+
+/// Удаляет хранилище, выданное директории `dir`. Саму директорию вызывающий удаляет сам:
+/// на macOS данные лежат не в ней, а в хранилище WebKit.
+/// Webview'ы, использующие хранилище, должны быть уже закрыты
+pub fn remove_storage(app: &AppHandle, dir: &Path) {
+    #[cfg(target_os = "macos")]
+    if ensure_separate_storage().is_ok() {
+        let app = app.clone();
+        let dir = dir.to_path_buf();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = app.remove_data_store(store_id(&dir)).await {
+                println!("🦀 Rust: Failed to remove webview data store {}: {}", dir.display(), e);
+            }
+        });
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = (app, dir);
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,15 @@
+use super::*;
+
+#[test]
+fn test_store_id_is_stable_per_directory() {
+    let work = Path::new("/data/containers/ct_1");
+    assert_eq!(store_id(work), store_id(&PathBuf::from("/data/containers/ct_1")));
+    assert_ne!(store_id(work), store_id(Path::new("/data/containers/ct_2")));
+}
+
+#[test]
+fn test_parse_major_version() {
+    assert_eq!(parse_major_version("14.2.1\n"), Some(14));
+    assert_eq!(parse_major_version("13.6"), Some(13));
+    assert_eq!(parse_major_version(""), None);
+}
@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, EventTarget, Manager};
+use tokio::io::AsyncWriteExt;
+use crate::registry::registry;
+use crate::error::LimniError;
+use crate::storage::{data_dir, read_json, write_json_atomic};
+use crate::utils::now_millis;
+
+/// Имя файла списка загрузок в директории данных приложения
+const DOWNLOADS_FILE: &str = "downloads.json";
+
+/// Минимальный интервал между событиями прогресса одной загрузки
+const PROGRESS_INTERVAL_MS: u64 = 250;
+
+/// Суффикс временного файла, пока загрузка не завершена
+const PARTIAL_SUFFIX: &str = ".part";
+
+/// Статус загрузки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadStatus {
+    InProgress,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Запись в списке загрузок
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadItem {
+    pub id: String,
+    pub url: String,
+    pub file_name: String,
+    pub path: Option<String>,
+    pub status: DownloadStatus,
+    pub received_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub error: Option<String>,
+    pub tab_id: Option<String>,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    #[serde(default)]
+    pub private: bool, // загрузка из приватного окна: не сохраняется на диск
+    /// Приватное окно, из которого начата загрузка: запись видит только оно
+    #[serde(skip)]
+    pub private_window: Option<String>,
+}
+
+impl DownloadItem {
+    /// Видна ли запись в окне `window_label`
+    pub fn visible_in(&self, window_label: &str) -> bool {
+        !self.private || self.private_window.as_deref() == Some(window_label)
+    }
+}
+
+/// Сообщает о загрузке во frontend. События приватных загрузок получает только их окно
+fn emit_download<S: Serialize + Clone>(app: &AppHandle, private_window: Option<&str>, event: &str, payload: S) {
+    let _ = match private_window {
+        Some(label) => app.emit_to(EventTarget::webview(label), event, payload),
+        None => app.emit(event, payload),
+    };
+}
+
+fn emit_item(app: &AppHandle, event: &str, item: &DownloadItem) {
+    emit_download(app, item.private_window.as_deref(), event, item);
+}
+
+/// Формат файла списка загрузок на диске
+#[derive(Default, Serialize, Deserialize)]
+struct DownloadsFile {
+    items: Vec<DownloadItem>,
+}
+
+/// Состояние менеджера загрузок
+#[derive(Default)]
+pub struct DownloadsState {
+    path: Option<PathBuf>,
+    pub items: Mutex<Vec<DownloadItem>>,
+    cancel_flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    /// Счётчик для id загрузок: время не уникально в пределах миллисекунды
+    next_id: AtomicU64,
+}
+
+impl DownloadsState {
+    /// Загружает список загрузок. Загрузки, прерванные закрытием приложения, помечаются как неудачные
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match data_dir(app) {
+            Ok(dir) => dir.join(DOWNLOADS_FILE),
+            Err(e) => {
+                println!("🦀 Rust: Downloads will not be persisted: {}", e);
+                return Self::default();
+            }
+        };
+
+        let mut file: DownloadsFile = read_json(&path).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load downloads, starting empty: {}", e);
+            DownloadsFile::default()
+        });
+        for item in file.items.iter_mut().filter(|i| i.status == DownloadStatus::InProgress) {
+            item.status = DownloadStatus::Failed;
+            item.error = Some("Interrupted".to_string());
+        }
+
+        Self { path: Some(path), items: Mutex::new(file.items), ..Self::default() }
+    }
+
+    /// Сохраняет список загрузок на диск
+    pub fn save(&self) -> Result<(), LimniError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let items = self.items.lock().unwrap().iter().filter(|i| !i.private).cloned().collect();
+        write_json_atomic(path, &DownloadsFile { items })
+    }
+
+    /// Изменяет запись загрузки и возвращает её новую копию
+    fn update_item<F: FnOnce(&mut DownloadItem)>(&self, id: &str, f: F) -> Option<DownloadItem> {
+        let mut items = self.items.lock().unwrap();
+        let item = items.iter_mut().find(|i| i.id == id)?;
+        f(item);
+        Some(item.clone())
+    }
+
+    /// Новый уникальный id загрузки
+    fn new_id(&self, now: u64) -> String {
+        format!("dl_{}_{}", now, self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn save_logged(&self) {
+        if let Err(e) = self.save() {
+            println!("🦀 Rust: Failed to save downloads: {}", e);
+        }
+    }
+}
+
+/// Директория для загрузок: `downloadPath` из настроек или системная папка загрузок
+fn download_dir(app: &AppHandle) -> Result<PathBuf, LimniError> {
+    let configured = crate::settings::current(app).download_path;
+    if !configured.trim().is_empty() {
+        return Ok(PathBuf::from(configured.trim()));
+    }
+
+    app.path().download_dir()
+        .or_else(|_| data_dir(app).map(|dir| dir.join("downloads")))
+        .map_err(|e| LimniError::io(format!("Failed to resolve download directory: {}", e)))
+}
+
+/// Декодирует %XX-последовательности (некорректные оставляет как есть)
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let hex = |b: u8| (b as char).to_digit(16);
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                result.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&result).to_string()
+}
+
+/// Извлекает имя файла из заголовка Content-Disposition (`filename*=` имеет приоритет)
+pub fn file_name_from_content_disposition(header: &str) -> Option<String> {
+    let mut plain = None;
+    for part in header.split(';').map(str::trim) {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                // Формат RFC 5987: charset'language'percent-encoded-value
+                let encoded = value.splitn(3, '\'').nth(2).unwrap_or(value);
+                let decoded = percent_decode(encoded);
+                if !decoded.is_empty() {
+                    return Some(decoded);
+                }
+            }
+            "filename" if !value.is_empty() => plain = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    plain
+}
+
+/// Имя файла по последнему сегменту пути URL
+pub fn file_name_from_url(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let segment = parsed.path_segments()?.rev().find(|s| !s.is_empty())?;
+    Some(percent_decode(segment))
+}
+
+/// Убирает из имени файла разделители путей и недопустимые символы
+pub fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').to_string();
+
+    if cleaned.is_empty() {
+        "download".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Путь к временному файлу незавершённой загрузки
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(PARTIAL_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Подбирает свободное имя: `file.zip`, `file (1).zip`, `file (2).zip`...
+pub fn unique_path(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() && !partial_path(&candidate).exists() {
+        return candidate;
+    }
+
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (file_name, String::new()),
+    };
+
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|p| !p.exists() && !partial_path(p).exists())
+        .unwrap()
+}
+
+/// Скачивает ли адрес менеджер загрузок. Остальные адреса (blob:, data:)
+/// существуют только внутри страницы, их скачивает сам webview
+pub fn is_managed_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Начинает загрузку файла менеджером загрузок и возвращает id загрузки.
+/// Загрузки приватного окна (`private_window`) не сохраняются в списке на диске
+pub fn start_download(app: &AppHandle, url: String, tab_id: Option<String>, file_name_hint: Option<String>, private_window: Option<String>) -> String {
+    let now = now_millis();
+    let state = app.state::<DownloadsState>();
+    let id = state.new_id(now);
+
+    let file_name = file_name_hint
+        .clone()
+        .or_else(|| file_name_from_url(&url))
+        .map(|name| sanitize_file_name(&name))
+        .unwrap_or_else(|| "download".to_string());
+
+    let item = DownloadItem {
+        id: id.clone(),
+        url,
+        file_name,
+        path: None,
+        status: DownloadStatus::InProgress,
+        received_bytes: 0,
+        total_bytes: None,
+        error: None,
+        tab_id,
+        started_at: now,
+        finished_at: None,
+        private: private_window.is_some(),
+        private_window,
+    };
+    state.items.lock().unwrap().insert(0, item.clone());
+    state.save_logged();
+    emit_item(app, "download-started", &item);
+
+    spawn_download(app, id.clone(), file_name_hint);
+    id
+}
+
+/// Повторяет неудачную или отменённую загрузку. Загрузки webview повторить нельзя:
+/// их адрес недоступен вне страницы
+pub fn retry_download_impl(app: &AppHandle, id: &str) -> Result<(), LimniError> {
+    let state = app.state::<DownloadsState>();
+    let managed = state.items.lock().unwrap()
+        .iter()
+        .find(|i| i.id == id)
+        .map(|i| is_managed_url(&i.url))
+        .ok_or_else(|| LimniError::not_found("Download", id))?;
+    if !managed {
+        return Err(LimniError::invalid_argument("Downloads made by the page cannot be retried"));
+    }
+
+    // Статус проверяется и меняется под одной блокировкой списка,
+    // поэтому два повтора подряд не запустят две передачи в один файл
+    let mut retryable = false;
+    let item = state
+        .update_item(id, |item| {
+            if item.status == DownloadStatus::Failed || item.status == DownloadStatus::Cancelled {
+                retryable = true;
+                item.status = DownloadStatus::InProgress;
+                item.received_bytes = 0;
+                item.error = None;
+                item.finished_at = None;
+            }
+        })
+        .ok_or_else(|| LimniError::not_found("Download", id))?;
+
+    if !retryable {
+        return Err(LimniError::invalid_argument("Download is not retryable"));
+    }
+
+    state.save_logged();
+    emit_item(app, "download-started", &item);
+    spawn_download(app, item.id, Some(item.file_name));
+    Ok(())
+}
+
+/// Отменяет выполняющуюся загрузку
+pub fn cancel_download_impl(app: &AppHandle, id: &str) -> Result<(), LimniError> {
+    let state = app.state::<DownloadsState>();
+    let flags = state.cancel_flags.lock().unwrap();
+    match flags.get(id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+        None => Err(LimniError::invalid_argument("Download is not in progress")),
+    }
+}
+
+/// Запускает загрузку в фоне и доводит запись до конечного статуса
+fn spawn_download(app: &AppHandle, id: String, file_name_hint: Option<String>) {
+    // Флаг отмены регистрируется до запуска задачи, чтобы загрузку можно было
+    // отменить сразу после старта
+    let cancel = Arc::new(AtomicBool::new(false));
+    app.state::<DownloadsState>().cancel_flags.lock().unwrap().insert(id.clone(), cancel.clone());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<DownloadsState>();
+        let result = transfer(&app, &id, file_name_hint, &cancel).await;
+        state.cancel_flags.lock().unwrap().remove(&id);
+
+        let (event, item) = match result {
+            Ok(()) => ("download-completed", state.update_item(&id, |item| {
+                item.status = DownloadStatus::Completed;
+                item.finished_at = Some(now_millis());
+            })),
+            Err(_) if cancel.load(Ordering::SeqCst) => ("download-cancelled", state.update_item(&id, |item| {
+                item.status = DownloadStatus::Cancelled;
+                item.finished_at = Some(now_millis());
+            })),
+            Err(err) => {
+                println!("🦀 Rust: Download {} failed: {}", id, err);
+                ("download-failed", state.update_item(&id, |item| {
+                    item.status = DownloadStatus::Failed;
+                    item.error = Some(err.to_string());
+                    item.finished_at = Some(now_millis());
+                }))
+            }
+        };
+
+        state.save_logged();
+        if let Some(item) = item {
+            emit_item(&app, event, &item);
+        }
+    });
+}
+
+/// Cookie вкладки, из которой началась загрузка, чтобы скачивались и файлы за авторизацией
+async fn cookie_header(app: &AppHandle, tab_id: Option<&str>, url: &str) -> Option<String> {
+    let tab = registry(app).get(tab_id?).await?;
+    let webview = app.get_webview(&tab.webview_label)?;
+    let cookies = webview.cookies_for_url(url.parse().ok()?).ok()?;
+
+    let header = cookies
+        .iter()
+        .map(|c| format!("{}={}", c.name(), c.value()))
+        .collect::<Vec<_>>()
+        .join("; ");
+    Some(header).filter(|h| !h.is_empty())
+}
+
+/// Скачивает файл во временный `.part` файл и переименовывает его после завершения
+async fn transfer(app: &AppHandle, id: &str, file_name_hint: Option<String>, cancel: &AtomicBool) -> Result<(), LimniError> {
+    let state = app.state::<DownloadsState>();
+    let (url, tab_id, private_window) = state
+        .update_item(id, |_| {})
+        .map(|item| (item.url, item.tab_id, item.private_window))
+        .ok_or_else(|| LimniError::not_found("Download", id))?;
+
+    let mut request = crate::net::network(app).client().get(&url);
+    if let Some(cookies) = cookie_header(app, tab_id.as_deref(), &url).await {
+        request = request.header(reqwest::header::COOKIE, cookies);
+    }
+
+    let mut response = request.send().await.map_err(|e| LimniError::network(format!("Request error: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(LimniError::network(format!("HTTP {}", response.status())));
+    }
+
+    let file_name = response.headers()
+        .get(reqwest::header::CONTENT_DISPOSITION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(file_name_from_content_disposition)
+        .or(file_name_hint)
+        .or_else(|| file_name_from_url(response.url().as_str()))
+        .map(|name| sanitize_file_name(&name))
+        .unwrap_or_else(|| "download".to_string());
+
+    let dir = download_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| LimniError::io(format!("Failed to create {}: {}", dir.display(), e)))?;
+    let path = unique_path(&dir, &file_name);
+    let part_path = partial_path(&path);
+    let total_bytes = response.content_length();
+
+    state.update_item(id, |item| {
+        item.file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(file_name);
+        item.path = Some(path.to_string_lossy().to_string());
+        item.total_bytes = total_bytes;
+    });
+    state.save_logged();
+
+    let mut file = tokio::fs::File::create(&part_path).await
+        .map_err(|e| LimniError::io(format!("Failed to create {}: {}", part_path.display(), e)))?;
+    let mut received_bytes = 0u64;
+    let mut last_progress = 0u64;
+
+    let result = async {
+        while let Some(chunk) = response.chunk().await.map_err(|e| LimniError::network(format!("Download error: {}", e)))? {
+            if cancel.load(Ordering::SeqCst) {
+                return Err(LimniError::internal("Cancelled"));
+            }
+
+            file.write_all(&chunk).await.map_err(|e| LimniError::io(format!("Write error: {}", e)))?;
+            received_bytes += chunk.len() as u64;
+
+            let now = now_millis();
+            if now - last_progress >= PROGRESS_INTERVAL_MS {
+                last_progress = now;
+                state.update_item(id, |item| item.received_bytes = received_bytes);
+                emit_download(app, private_window.as_deref(), "download-progress", serde_json::json!({
+                    "id": id,
+                    "receivedBytes": received_bytes,
+                    "totalBytes": total_bytes
+                }));
+            }
+        }
+        file.flush().await.map_err(|e| LimniError::io(format!("Write error: {}", e)))
+    }
+    .await;
+    drop(file);
+
+    if let Err(err) = result {
+        let _ = tokio::fs::remove_file(&part_path).await;
+        return Err(err);
+    }
+
+    tokio::fs::rename(&part_path, &path).await
+        .map_err(|e| LimniError::io(format!("Failed to move {}: {}", part_path.display(), e)))?;
+    state.update_item(id, |item| item.received_bytes = received_bytes);
+    println!("🦀 Rust: Download {} saved to {}", id, path.display());
+    Ok(())
+}
+
+/// Загрузки, которые не получится повторить по HTTP (blob:, data:), оставляем webview:
+/// указываем путь в папке загрузок и запоминаем запись в списке
+pub fn track_native_download(app: &AppHandle, url: &str, destination: &mut PathBuf, tab_id: &str, private_window: Option<String>) {
+    let Ok(dir) = download_dir(app) else {
+        return;
+    };
+    let file_name = destination
+        .file_name()
+        .map(|n| sanitize_file_name(&n.to_string_lossy()))
+        .unwrap_or_else(|| "download".to_string());
+    let _ = std::fs::create_dir_all(&dir);
+    *destination = unique_path(&dir, &file_name);
+
+    let now = now_millis();
+    let state = app.state::<DownloadsState>();
+    let item = {
+        let mut items = state.items.lock().unwrap();
+        let item = DownloadItem {
+            id: state.new_id(now),
+            url: url.to_string(),
+            file_name: destination.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(file_name),
+            path: Some(destination.to_string_lossy().to_string()),
+            status: DownloadStatus::InProgress,
+            received_bytes: 0,
+            total_bytes: None,
+            error: None,
+            tab_id: Some(tab_id.to_string()),
+            started_at: now,
+            finished_at: None,
+            private: private_window.is_some(),
+            private_window,
+        };
+        items.insert(0, item.clone());
+        item
+    };
+    state.save_logged();
+    emit_item(app, "download-started", &item);
+}
+
+/// Завершает запись загрузки, выполненной самим webview
+pub fn finish_native_download(app: &AppHandle, url: &str, success: bool) {
+    let state = app.state::<DownloadsState>();
+    let id = {
+        let items = state.items.lock().unwrap();
+        items
+            .iter()
+            .find(|i| i.url == url && i.status == DownloadStatus::InProgress)
+            .map(|i| i.id.clone())
+    };
+    let Some(id) = id else {
+        return;
+    };
+
+    let item = state.update_item(&id, |item| {
+        item.status = if success { DownloadStatus::Completed } else { DownloadStatus::Failed };
+        item.finished_at = Some(now_millis());
+        if let Some(size) = item.path.as_ref().and_then(|p| std::fs::metadata(p).ok()).map(|m| m.len()) {
+            item.received_bytes = size;
+            item.total_bytes = Some(size);
+        }
+    });
+    state.save_logged();
+
+    if let Some(item) = item {
+        let event = if success { "download-completed" } else { "download-failed" };
+        emit_item(app, event, &item);
+    }
+}
+
+#[cfg(test)]
+mod tests;
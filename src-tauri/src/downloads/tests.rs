@@ -0,0 +1,76 @@
+use super::*;
+
+#[test]
+fn test_file_name_from_content_disposition() {
+    assert_eq!(
+        file_name_from_content_disposition("attachment; filename=\"report.pdf\"").as_deref(),
+        Some("report.pdf")
+    );
+    assert_eq!(
+        file_name_from_content_disposition("attachment; filename=\"fallback.txt\"; filename*=UTF-8''%D0%BE%D1%82%D1%87%D1%91%D1%82.txt").as_deref(),
+        Some("отчёт.txt")
+    );
+    assert_eq!(file_name_from_content_disposition("inline"), None);
+}
+
+#[test]
+fn test_download_ids_are_unique_within_a_millisecond() {
+    let state = DownloadsState::default();
+    assert_ne!(state.new_id(100), state.new_id(100));
+}
+
+#[test]
+fn test_private_downloads_are_visible_only_in_their_window() {
+    let item = DownloadItem {
+        id: "dl_1".to_string(),
+        url: "https://example.com/file.zip".to_string(),
+        file_name: "file.zip".to_string(),
+        path: None,
+        status: DownloadStatus::InProgress,
+        received_bytes: 0,
+        total_bytes: None,
+        error: None,
+        tab_id: None,
+        started_at: 100,
+        finished_at: None,
+        private: true,
+        private_window: Some("private-1".to_string()),
+    };
+    assert!(item.visible_in("private-1"));
+    assert!(!item.visible_in("main"));
+    assert!(!serde_json::to_string(&item).unwrap().contains("private-1"));
+
+    let public = DownloadItem { private: false, private_window: None, ..item };
+    assert!(public.visible_in("main"));
+}
+
+#[test]
+fn test_only_http_downloads_are_managed() {
+    assert!(is_managed_url("https://example.com/file.zip"));
+    assert!(is_managed_url("http://example.com/file.zip"));
+    assert!(!is_managed_url("blob:https://example.com/1f2e"));
+    assert!(!is_managed_url("data:text/plain,hello"));
+}
+
+#[test]
+fn test_file_name_from_url_and_sanitize() {
+    assert_eq!(file_name_from_url("https://example.com/files/my%20file.zip?x=1").as_deref(), Some("my file.zip"));
+    assert_eq!(file_name_from_url("https://example.com/"), None);
+    assert_eq!(sanitize_file_name("../etc/passwd"), "_etc_passwd");
+    assert_eq!(sanitize_file_name(" .. "), "download");
+}
+
+#[test]
+fn test_unique_path_skips_existing_files() {
+    let dir = std::env::temp_dir().join(format!("limni_downloads_test_{}", now_millis()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    assert_eq!(unique_path(&dir, "file.zip"), dir.join("file.zip"));
+    std::fs::write(dir.join("file.zip"), b"x").unwrap();
+    std::fs::write(dir.join("file (1).zip.part"), b"x").unwrap();
+    assert_eq!(unique_path(&dir, "file.zip"), dir.join("file (2).zip"));
+    std::fs::write(dir.join("README"), b"x").unwrap();
+    assert_eq!(unique_path(&dir, "README"), dir.join("README (1)"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
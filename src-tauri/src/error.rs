@@ -0,0 +1,165 @@
+use std::fmt;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// Ошибка команд Limni. Во frontend приходит объектом
+/// `{ code, message, ...поля варианта }`, где `code` — стабильный код ошибки,
+/// по которому frontend решает, что делать (например, пересоздать пропавший webview)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimniError {
+    /// Вкладка неизвестна бэкенду (webview для неё не создавался или уже закрыт)
+    TabNotFound { tab_id: String },
+    /// Вкладка известна, но её webview больше не существует
+    WebviewGone { tab_id: String },
+    WindowNotFound { window_label: String },
+    InvalidUrl { url: String, reason: String },
+    UnsupportedScheme { url: String },
+    /// Запись (закладка, загрузка, контейнер, профиль...) не найдена
+    NotFound { entity: &'static str, id: String },
+    /// Некорректные аргументы команды или недопустимая операция
+    InvalidArgument { message: String },
+    Network { message: String },
+    Io { message: String },
+    /// Операция не завершилась за отведённое время
+    Timeout { message: String },
+    /// Скрипт, выполненный во вкладке, бросил исключение или вернул несериализуемое значение
+    Script { tab_id: String, message: String },
+    /// Команда интерфейса браузера вызвана страницей вкладки
+    Forbidden { webview_label: String },
+    /// Ошибка Tauri/webview или другая внутренняя ошибка
+    Internal { message: String },
+}
+
+impl LimniError {
+    /// Стабильный код ошибки для frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::TabNotFound { .. } => "TAB_NOT_FOUND",
+            Self::WebviewGone { .. } => "WEBVIEW_GONE",
+            Self::WindowNotFound { .. } => "WINDOW_NOT_FOUND",
+            Self::InvalidUrl { .. } => "INVALID_URL",
+            Self::UnsupportedScheme { .. } => "UNSUPPORTED_SCHEME",
+            Self::NotFound { .. } => "NOT_FOUND",
+            Self::InvalidArgument { .. } => "INVALID_ARGUMENT",
+            Self::Network { .. } => "NETWORK",
+            Self::Io { .. } => "IO",
+            Self::Timeout { .. } => "TIMEOUT",
+            Self::Script { .. } => "SCRIPT_ERROR",
+            Self::Forbidden { .. } => "FORBIDDEN",
+            Self::Internal { .. } => "INTERNAL",
+        }
+    }
+
+    pub fn tab_not_found(tab_id: impl Into<String>) -> Self {
+        Self::TabNotFound { tab_id: tab_id.into() }
+    }
+
+    pub fn webview_gone(tab_id: impl Into<String>) -> Self {
+        Self::WebviewGone { tab_id: tab_id.into() }
+    }
+
+    pub fn window_not_found(window_label: impl Into<String>) -> Self {
+        Self::WindowNotFound { window_label: window_label.into() }
+    }
+
+    pub fn invalid_url(url: impl Into<String>, reason: impl fmt::Display) -> Self {
+        Self::InvalidUrl { url: url.into(), reason: reason.to_string() }
+    }
+
+    pub fn unsupported_scheme(url: impl Into<String>) -> Self {
+        Self::UnsupportedScheme { url: url.into() }
+    }
+
+    pub fn not_found(entity: &'static str, id: impl Into<String>) -> Self {
+        Self::NotFound { entity, id: id.into() }
+    }
+
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::InvalidArgument { message: message.into() }
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::Network { message: message.into() }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::Io { message: message.into() }
+    }
+
+    pub fn timeout(message: impl Into<String>) -> Self {
+        Self::Timeout { message: message.into() }
+    }
+
+    pub fn script(tab_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Script { tab_id: tab_id.into(), message: message.into() }
+    }
+
+    pub fn forbidden(webview_label: impl Into<String>) -> Self {
+        Self::Forbidden { webview_label: webview_label.into() }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal { message: message.into() }
+    }
+}
+
+impl fmt::Display for LimniError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TabNotFound { tab_id } => write!(f, "Tab not found: {}", tab_id),
+            Self::WebviewGone { tab_id } => write!(f, "Webview of tab {} no longer exists", tab_id),
+            Self::WindowNotFound { window_label } => write!(f, "Window not found: {}", window_label),
+            Self::InvalidUrl { url, reason } => write!(f, "Invalid URL {}: {}", url, reason),
+            Self::UnsupportedScheme { url } => write!(f, "Unsupported URL scheme: {}", url),
+            Self::NotFound { entity, id } => write!(f, "{} not found: {}", entity, id),
+            Self::Script { tab_id, message } => write!(f, "Script failed in tab {}: {}", tab_id, message),
+            Self::Forbidden { webview_label } => write!(f, "Browser commands are not available to {}", webview_label),
+            Self::InvalidArgument { message }
+            | Self::Network { message }
+            | Self::Io { message }
+            | Self::Timeout { message }
+            | Self::Internal { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for LimniError {}
+
+impl Serialize for LimniError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::TabNotFound { tab_id } | Self::WebviewGone { tab_id } | Self::Script { tab_id, .. } => {
+                map.serialize_entry("tabId", tab_id)?;
+            }
+            Self::WindowNotFound { window_label } => {
+                map.serialize_entry("windowLabel", window_label)?;
+            }
+            Self::InvalidUrl { url, .. } | Self::UnsupportedScheme { url } => {
+                map.serialize_entry("url", url)?;
+            }
+            Self::NotFound { entity, id } => {
+                map.serialize_entry("entity", entity)?;
+                map.serialize_entry("id", id)?;
+            }
+            _ => {}
+        }
+        map.end()
+    }
+}
+
+impl From<tauri::Error> for LimniError {
+    fn from(e: tauri::Error) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for LimniError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::network(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn test_error_serializes_code_message_and_fields() {
+    let value = serde_json::to_value(LimniError::webview_gone("tab_1")).unwrap();
+    assert_eq!(value, serde_json::json!({
+        "code": "WEBVIEW_GONE",
+        "message": "Webview of tab tab_1 no longer exists",
+        "tabId": "tab_1"
+    }));
+
+    let value = serde_json::to_value(LimniError::not_found("Bookmark", "bm_1")).unwrap();
+    assert_eq!(value["code"], "NOT_FOUND");
+    assert_eq!(value["entity"], "Bookmark");
+    assert_eq!(value["message"], "Bookmark not found: bm_1");
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    assert_eq!(LimniError::tab_not_found("t").code(), "TAB_NOT_FOUND");
+    assert_eq!(LimniError::unsupported_scheme("ftp://x").code(), "UNSUPPORTED_SCHEME");
+    assert_eq!(LimniError::invalid_url("x", "relative URL").code(), "INVALID_URL");
+    assert_eq!(LimniError::network("timeout").code(), "NETWORK");
+    assert_eq!(LimniError::io("disk full").code(), "IO");
+    assert_eq!(LimniError::timeout("no answer").code(), "TIMEOUT");
+    assert_eq!(LimniError::script("t", "ReferenceError").code(), "SCRIPT_ERROR");
+    assert_eq!(LimniError::forbidden("tab-1").code(), "FORBIDDEN");
+}
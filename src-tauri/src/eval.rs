@@ -0,0 +1,137 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+use crate::error::LimniError;
+use crate::registry::tab_webview;
+
+/// Сколько по умолчанию ждём результат скрипта
+pub const DEFAULT_EVAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// IPC команда, которой вкладка возвращает результат скрипта
+pub const EVAL_CALLBACK_COMMAND: &str = "eval_in_tab_callback";
+
+type EvalReply = oneshot::Sender<Result<Value, String>>;
+
+struct PendingEval {
+    webview_label: String,
+    reply: EvalReply,
+}
+
+/// Скрипты, которые выполняются во вкладках и ждут ответа
+#[derive(Default)]
+pub struct EvalState {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<String, PendingEval>>,
+}
+
+impl EvalState {
+    /// Регистрирует запрос. Id содержит случайную часть, чтобы страница
+    /// не могла подделать ответ на чужой запрос
+    pub fn register(&self, webview_label: &str) -> (String, oneshot::Receiver<Result<Value, String>>) {
+        let counter = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(counter);
+        let request_id = format!("ev_{}_{:016x}", counter, hasher.finish());
+
+        let (reply, response) = oneshot::channel();
+        self.pending.lock().unwrap().insert(request_id.clone(), PendingEval {
+            webview_label: webview_label.to_string(),
+            reply,
+        });
+        (request_id, response)
+    }
+
+    pub fn cancel(&self, request_id: &str) {
+        self.pending.lock().unwrap().remove(request_id);
+    }
+
+    /// Передаёт ответ вкладки ожидающему запросу. Ответ принимается только
+    /// от того webview, в котором выполнялся скрипт
+    pub fn resolve(&self, request_id: &str, webview_label: &str, result: Result<Value, String>) -> bool {
+        let mut pending = self.pending.lock().unwrap();
+        match pending.get(request_id) {
+            Some(request) if request.webview_label == webview_label => {}
+            _ => return false,
+        }
+        let request = pending.remove(request_id).expect("checked above");
+        drop(pending);
+        request.reply.send(result).is_ok()
+    }
+
+    #[cfg(test)]
+    fn pending_count(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+}
+
+/// Оборачивает выражение так, чтобы его результат (в том числе результат Promise)
+/// вернулся в бэкенд через IPC команду. `window.__TAURI_INTERNALS__` есть во всех
+/// webview, в отличие от `window.__TAURI__`, который включается только `withGlobalTauri`
+pub fn wrap_script(request_id: &str, script: &str) -> String {
+    let expression = script.trim().trim_end_matches(';');
+    let request_id = serde_json::to_string(request_id).expect("string serialization");
+    format!(
+        r#"(function () {{
+    var requestId = {request_id};
+    function send(ok, value, error) {{
+        var ipc = window.__TAURI_INTERNALS__;
+        if (!ipc) return;
+        ipc.invoke('{command}', {{ requestId: requestId, ok: ok, value: value, error: error }}).catch(function () {{}});
+    }}
+    function fail(error) {{
+        send(false, null, String((error && error.message) || error));
+    }}
+    try {{
+        Promise.resolve((function () {{ return (
+{expression}
+); }})()).then(function (value) {{
+            var json;
+            try {{
+                json = JSON.stringify(value);
+            }} catch (error) {{
+                fail('Result is not serializable: ' + error);
+                return;
+            }}
+            send(true, json === undefined ? null : JSON.parse(json), null);
+        }}, fail);
+    }} catch (error) {{
+        fail(error);
+    }}
+}})();"#,
+        request_id = request_id,
+        command = EVAL_CALLBACK_COMMAND,
+        expression = expression,
+    )
+}
+
+/// Выполняет JavaScript выражение во вкладке и возвращает его значение.
+/// Если выражение возвращает Promise, ждём его результат, но не дольше `timeout`
+pub async fn eval_in_tab(app: &AppHandle, tab_id: &str, script: &str, timeout: Duration) -> Result<Value, LimniError> {
+    let webview = tab_webview(app, tab_id).await?;
+    let state = app.state::<EvalState>();
+    let (request_id, response) = state.register(webview.label());
+
+    if let Err(e) = webview.eval(wrap_script(&request_id, script)) {
+        state.cancel(&request_id);
+        return Err(LimniError::internal(format!("Failed to evaluate script: {}", e)));
+    }
+
+    match tokio::time::timeout(timeout, response).await {
+        Ok(Ok(Ok(value))) => Ok(value),
+        Ok(Ok(Err(message))) => Err(LimniError::script(tab_id, message)),
+        Ok(Err(_)) => Err(LimniError::internal("Script result channel closed")),
+        Err(_) => {
+            state.cancel(&request_id);
+            Err(LimniError::timeout(format!("Script in tab {} did not respond in {} ms", tab_id, timeout.as_millis())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
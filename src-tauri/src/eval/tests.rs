@@ -0,0 +1,40 @@
+use super::*;
+
+#[tokio::test]
+async fn test_resolve_delivers_result_to_waiting_request() {
+    let state = EvalState::default();
+    let (request_id, response) = state.register("tab-1");
+
+    assert!(state.resolve(&request_id, "tab-1", Ok(serde_json::json!("Title"))));
+    assert_eq!(response.await.unwrap(), Ok(serde_json::json!("Title")));
+    assert_eq!(state.pending_count(), 0);
+}
+
+#[test]
+fn test_resolve_rejects_unknown_id_and_other_webview() {
+    let state = EvalState::default();
+    let (request_id, _response) = state.register("tab-1");
+
+    assert!(!state.resolve("ev_0_0000000000000000", "tab-1", Ok(Value::Null)));
+    assert!(!state.resolve(&request_id, "tab-2", Ok(Value::Null)));
+    assert_eq!(state.pending_count(), 1);
+
+    state.cancel(&request_id);
+    assert_eq!(state.pending_count(), 0);
+}
+
+#[test]
+fn test_request_ids_are_unique() {
+    let state = EvalState::default();
+    let (first, _a) = state.register("tab-1");
+    let (second, _b) = state.register("tab-1");
+    assert_ne!(first, second);
+}
+
+#[test]
+fn test_wrap_script_embeds_expression_and_escaped_id() {
+    let script = wrap_script("ev_1_\"x", "document.title;\n");
+    assert!(script.contains(r#"var requestId = "ev_1_\"x";"#));
+    assert!(script.contains("\ndocument.title\n"));
+    assert!(script.contains(EVAL_CALLBACK_COMMAND));
+}
@@ -0,0 +1,477 @@
+use base64::{engine::general_purpose, Engine as _};
+use serde::Deserialize;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use tauri::{AppHandle, Manager};
+use url::Url;
+use crate::error::LimniError;
+use crate::net::{network, FetchedResponse};
+use crate::settings;
+use crate::utils::{document_base, fetch_html_head, html_tags, now_millis};
+
+pub mod cache;
+pub mod fallback;
+
+use cache::{cache_key, CacheEntry, FaviconCacheState};
+use fallback::FaviconFallback;
+
+/// Размер значка, под который выбираем вариант: 16px на панели вкладок
+/// и в закладках при масштабе 2x
+pub const PREFERRED_ICON_SIZE: u32 = 32;
+
+/// Наибольший размер фавиконки
+pub const MAX_FAVICON_BYTES: usize = 1024 * 1024;
+
+const MAX_MANIFEST_BYTES: usize = 256 * 1024;
+
+/// Сколько объявленных значков пробуем загрузить до `/favicon.ico`
+const MAX_ICON_ATTEMPTS: usize = 4;
+
+/// Откуда взят значок
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconSource {
+    /// `<link rel="icon">`
+    Link,
+    /// `<link rel="apple-touch-icon">`
+    AppleTouch,
+    /// Значок из web app manifest
+    Manifest,
+    /// `/favicon.ico` сайта
+    Default,
+}
+
+/// Размеры из атрибута `sizes`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IconSizes {
+    /// Масштабируемый значок (`sizes="any"`)
+    Any,
+    /// Наибольшая сторона каждого объявленного размера
+    Fixed(Vec<u32>),
+    Unknown,
+}
+
+/// Значок, объявленный страницей
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconCandidate {
+    pub url: Url,
+    pub source: IconSource,
+    pub sizes: IconSizes,
+    pub mime: Option<String>,
+    /// Значок только для отдельной цветовой схемы или экрана (атрибут `media`)
+    pub media: bool,
+}
+
+impl IconCandidate {
+    fn new(url: Url, source: IconSource, sizes: IconSizes, mime: Option<String>) -> Self {
+        Self { url, source, sizes, mime, media: false }
+    }
+
+    fn is_svg(&self) -> bool {
+        self.mime.as_deref() == Some("image/svg+xml") || self.url.path().to_ascii_lowercase().ends_with(".svg")
+    }
+
+    /// Ключ сортировки, меньше — лучше. Векторный значок подходит для любого
+    /// масштаба; из растровых берём наименьший не меньше `PREFERRED_ICON_SIZE`,
+    /// а если таких нет — наибольший
+    fn rank(&self) -> (bool, u8, u32) {
+        let (tier, distance) = match &self.sizes {
+            _ if self.is_svg() => (0, 0),
+            IconSizes::Any => (0, 0),
+            IconSizes::Fixed(sizes) => {
+                match sizes.iter().filter(|size| **size >= PREFERRED_ICON_SIZE).min() {
+                    Some(size) => (1, size - PREFERRED_ICON_SIZE),
+                    None => (2, PREFERRED_ICON_SIZE - sizes.iter().max().copied().unwrap_or(0)),
+                }
+            }
+            IconSizes::Unknown if self.source == IconSource::Default => (4, 0),
+            IconSizes::Unknown => (3, 0),
+        };
+        (self.media, tier, distance)
+    }
+}
+
+/// Значки и manifest, объявленные в HTML страницы
+#[derive(Debug, Default, PartialEq)]
+pub struct PageIcons {
+    pub icons: Vec<IconCandidate>,
+    pub manifest: Option<Url>,
+}
+
+/// Разбирает атрибут `sizes`: "16x16 32x32", "any"
+pub fn parse_sizes(value: Option<&str>) -> IconSizes {
+    let Some(value) = value else {
+        return IconSizes::Unknown;
+    };
+    let mut sizes = Vec::new();
+    for token in value.split_whitespace() {
+        if token.eq_ignore_ascii_case("any") {
+            return IconSizes::Any;
+        }
+        let parsed = token.to_ascii_lowercase().split_once('x')
+            .and_then(|(w, h)| Some(w.parse::<u32>().ok()?.max(h.parse::<u32>().ok()?)));
+        if let Some(size) = parsed.filter(|size| *size > 0) {
+            sizes.push(size);
+        }
+    }
+    if sizes.is_empty() { IconSizes::Unknown } else { IconSizes::Fixed(sizes) }
+}
+
+fn is_supported_icon_url(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https" | "data")
+}
+
+/// Находит в HTML объявления значков (`icon`, `apple-touch-icon`) и ссылку
+/// на manifest. Относительные адреса разрешаются от `<base href>` или адреса страницы
+pub fn parse_page_icons(html: &str, page_url: &Url) -> PageIcons {
+    let base = document_base(html, page_url);
+    let mut page = PageIcons::default();
+    for link in html_tags(html, &["link"]) {
+        let (Some(rel), Some(href)) = (link.attr("rel"), link.attr("href")) else {
+            continue;
+        };
+        let Some(url) = base.join(href).ok().filter(is_supported_icon_url) else {
+            continue;
+        };
+        let rel = rel.to_ascii_lowercase();
+        let rel: Vec<&str> = rel.split_whitespace().collect();
+
+        if rel.contains(&"manifest") {
+            page.manifest.get_or_insert(url);
+            continue;
+        }
+        let source = if rel.contains(&"icon") {
+            IconSource::Link
+        } else if rel.iter().any(|token| matches!(*token, "apple-touch-icon" | "apple-touch-icon-precomposed")) {
+            IconSource::AppleTouch
+        } else {
+            continue;
+        };
+
+        let mut sizes = parse_sizes(link.attr("sizes"));
+        if source == IconSource::AppleTouch && sizes == IconSizes::Unknown {
+            // Размер apple-touch-icon по умолчанию
+            sizes = IconSizes::Fixed(vec![180]);
+        }
+        let mime = link.attr("type").map(|mime| mime.to_ascii_lowercase());
+        let mut icon = IconCandidate::new(url, source, sizes, mime);
+        icon.media = link.attr("media").is_some();
+        page.icons.push(icon);
+    }
+    page
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    icons: Vec<ManifestIcon>,
+}
+
+#[derive(Deserialize)]
+struct ManifestIcon {
+    src: String,
+    sizes: Option<String>,
+    #[serde(rename = "type")]
+    mime: Option<String>,
+    purpose: Option<String>,
+}
+
+/// Значки из web app manifest. Значки только для маски или монохромные
+/// (`purpose` без `any`) на панели вкладок выглядят чужими, их пропускаем
+pub fn parse_manifest_icons(json: &str, manifest_url: &Url) -> Vec<IconCandidate> {
+    let Ok(manifest) = serde_json::from_str::<Manifest>(json) else {
+        return Vec::new();
+    };
+    manifest.icons.into_iter()
+        .filter(|icon| {
+            icon.purpose.as_deref()
+                .is_none_or(|purpose| purpose.split_whitespace().any(|p| p.eq_ignore_ascii_case("any")))
+        })
+        .filter_map(|icon| {
+            let url = manifest_url.join(icon.src.trim()).ok().filter(is_supported_icon_url)?;
+            let mime = icon.mime.map(|mime| mime.to_ascii_lowercase());
+            Some(IconCandidate::new(url, IconSource::Manifest, parse_sizes(icon.sizes.as_deref()), mime))
+        })
+        .collect()
+}
+
+/// `/favicon.ico` сайта
+pub fn default_icon(page_url: &Url) -> Option<IconCandidate> {
+    let url = page_url.join("/favicon.ico").ok()?;
+    Some(IconCandidate::new(url, IconSource::Default, IconSizes::Unknown, None))
+}
+
+/// Упорядочивает значки от лучшего к худшему и убирает повторы
+pub fn rank_candidates(mut icons: Vec<IconCandidate>) -> Vec<IconCandidate> {
+    icons.sort_by_key(IconCandidate::rank);
+    let mut unique: Vec<IconCandidate> = Vec::with_capacity(icons.len());
+    for icon in icons {
+        if !unique.iter().any(|known| known.url == icon.url) {
+            unique.push(icon);
+        }
+    }
+    unique
+}
+
+/// Определяет формат изображения по содержимому. Заголовку Content-Type
+/// не доверяем: вместо `/favicon.ico` сайты часто отдают HTML страницу
+pub fn sniff_image(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some("image/png"),
+        [0x00, 0x00, 0x01, 0x00, count_lo, count_hi, ..] if *count_lo != 0 || *count_hi != 0 => Some("image/x-icon"),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("image/gif"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        [b'B', b'M', ..] if bytes.len() > 26 => Some("image/bmp"),
+        _ if is_svg(bytes) => Some("image/svg+xml"),
+        _ => None,
+    }
+}
+
+/// SVG документ: до корневого `<svg` допускаются только XML пролог,
+/// DOCTYPE и комментарии
+fn is_svg(bytes: &[u8]) -> bool {
+    let prefix = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]).to_ascii_lowercase();
+    let text = prefix.trim_start_matches('\u{feff}').trim_start();
+    let Some(svg) = text.find("<svg") else {
+        return false;
+    };
+    let preamble = &text[..svg];
+    text.starts_with('<') && !["<html", "<head", "<body"].iter().any(|tag| preamble.contains(tag))
+}
+
+/// Проверенная фавиконка
+#[derive(Debug, Clone, PartialEq)]
+pub struct Favicon {
+    pub mime: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+impl Favicon {
+    /// Принимает байты, только если это изображение
+    pub fn from_bytes(bytes: Vec<u8>) -> Option<Self> {
+        sniff_image(&bytes).map(|mime| Self { mime, bytes })
+    }
+
+    pub fn to_data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime, general_purpose::STANDARD.encode(&self.bytes))
+    }
+}
+
+/// Содержимое `data:` адреса в base64
+fn decode_data_url(url: &Url) -> Option<Vec<u8>> {
+    let (meta, payload) = url.as_str().strip_prefix("data:")?.split_once(',')?;
+    if !meta.to_ascii_lowercase().ends_with(";base64") {
+        return None;
+    }
+    general_purpose::STANDARD.decode(payload.trim()).ok()
+}
+
+/// Значок вместе с адресом, откуда он загружен, и валидаторами HTTP кэша
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedIcon {
+    pub favicon: Favicon,
+    pub url: Url,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl ResolvedIcon {
+    /// Проверяет ответ сервера на запрос значка
+    fn from_response(url: &Url, response: FetchedResponse) -> Result<Self, LimniError> {
+        if !response.status.is_success() {
+            return Err(LimniError::network(format!("HTTP {} for {}", response.status, url)));
+        }
+        let etag = response.header(ETAG);
+        let last_modified = response.header(LAST_MODIFIED);
+        let favicon = Favicon::from_bytes(response.body)
+            .ok_or_else(|| LimniError::network(format!("{} is not an image", url)))?;
+        Ok(Self { favicon, url: url.clone(), etag, last_modified })
+    }
+}
+
+/// Загружает значок и проверяет, что это изображение
+async fn download_icon(app: &AppHandle, url: &Url) -> Result<ResolvedIcon, LimniError> {
+    if url.scheme() == "data" {
+        return decode_data_url(url)
+            .and_then(Favicon::from_bytes)
+            .map(|favicon| ResolvedIcon { favicon, url: url.clone(), etag: None, last_modified: None })
+            .ok_or_else(|| LimniError::invalid_argument("Inline icon is not an image"));
+    }
+
+    let network = network(app);
+    let response = network.fetch(network.get(url.as_str()), MAX_FAVICON_BYTES).await?;
+    ResolvedIcon::from_response(url, response)
+}
+
+async fn fetch_manifest_icons(app: &AppHandle, manifest_url: &Url) -> Result<Vec<IconCandidate>, LimniError> {
+    let network = network(app);
+    let response = network.fetch(network.get(manifest_url.as_str()), MAX_MANIFEST_BYTES).await?;
+    if !response.status.is_success() {
+        return Err(LimniError::network(format!("HTTP {} for {}", response.status, manifest_url)));
+    }
+    Ok(parse_manifest_icons(&String::from_utf8_lossy(&response.body), manifest_url))
+}
+
+/// Ищет значки, объявленные страницей. Manifest загружаем, только если
+/// в HTML значков нет. Возвращает значки по убыванию качества и адрес
+/// страницы после редиректов
+async fn discover_icons(app: &AppHandle, page_url: &Url) -> Result<(Vec<IconCandidate>, Url), LimniError> {
+    let Some(head) = fetch_html_head(app, page_url).await? else {
+        return Ok((Vec::new(), page_url.clone()));
+    };
+
+    let page = parse_page_icons(&head.html, &head.url);
+    let mut icons = page.icons;
+    if icons.is_empty() {
+        if let Some(manifest_url) = page.manifest {
+            match fetch_manifest_icons(app, &manifest_url).await {
+                Ok(manifest_icons) => icons = manifest_icons,
+                Err(e) => println!("🦀 Rust: Failed to fetch manifest {}: {}", manifest_url, e),
+            }
+        }
+    }
+    Ok((rank_candidates(icons), head.url))
+}
+
+/// Находит значок сайта: лучший из объявленных страницей, затем `/favicon.ico`
+pub async fn resolve_favicon(app: &AppHandle, page_url: &Url) -> Result<ResolvedIcon, LimniError> {
+    let (declared, site_url) = match discover_icons(app, page_url).await {
+        Ok(found) => found,
+        Err(e) => {
+            println!("🦀 Rust: Failed to read icon declarations of {}: {}", page_url, e);
+            (Vec::new(), page_url.clone())
+        }
+    };
+    let mut candidates: Vec<IconCandidate> = declared.into_iter().take(MAX_ICON_ATTEMPTS).collect();
+    if let Some(default) = default_icon(&site_url).filter(|icon| !candidates.iter().any(|c| c.url == icon.url)) {
+        candidates.push(default);
+    }
+
+    for candidate in &candidates {
+        match download_icon(app, &candidate.url).await {
+            Ok(icon) => return Ok(icon),
+            Err(e) => println!("🦀 Rust: Skipping favicon candidate {}: {}", candidate.url, e),
+        }
+    }
+    Err(LimniError::network("Failed to fetch favicon."))
+}
+
+/// Значок на случай, когда у сайта своего нет, по политике из настроек.
+/// Внешний сервис не узнаёт ни о приватных окнах, ни о локальных и интранет хостах
+async fn fallback_favicon(app: &AppHandle, page_url: &Url, private: bool) -> Result<Favicon, LimniError> {
+    let settings = settings::current(app);
+    let host = page_url.host_str().unwrap_or_default();
+    match FaviconFallback::from_settings(&settings.favicon_fallback, &settings.favicon_service_url) {
+        FaviconFallback::Disabled => Err(LimniError::not_found("favicon", host)),
+        FaviconFallback::Service(_) if !fallback::may_use_service(page_url, private) => Ok(fallback::letter_icon(host)),
+        FaviconFallback::Service(template) => {
+            let service_url = fallback::service_icon_url(&template, host)?;
+            download_icon(app, &service_url).await.map(|icon| icon.favicon)
+        }
+        FaviconFallback::Letter => Ok(fallback::letter_icon(host)),
+    }
+}
+
+/// Итог проверки устаревшего значка на сервере
+enum Revalidation {
+    NotModified,
+    Updated(ResolvedIcon),
+    /// Значка по старому адресу больше нет, нужно искать заново
+    Gone,
+    /// Сервер недоступен: пока показываем то, что есть в кэше
+    Unreachable,
+}
+
+/// Условный запрос значка по валидаторам из кэша (If-None-Match / If-Modified-Since)
+async fn revalidate(app: &AppHandle, entry: &CacheEntry) -> Revalidation {
+    let Some(url) = Url::parse(&entry.icon_url).ok().filter(|url| url.scheme() != "data") else {
+        return Revalidation::Gone;
+    };
+
+    let network = network(app);
+    let mut request = network.get(url.as_str());
+    if let Some(etag) = &entry.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &entry.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+
+    match network.fetch(request, MAX_FAVICON_BYTES).await {
+        Ok(response) if response.status == StatusCode::NOT_MODIFIED => Revalidation::NotModified,
+        Ok(response) => match ResolvedIcon::from_response(&url, response) {
+            Ok(icon) => Revalidation::Updated(icon),
+            Err(_) => Revalidation::Gone,
+        },
+        Err(_) => Revalidation::Unreachable,
+    }
+}
+
+/// Значок страницы: свежий из кэша сразу, устаревший — после проверки на сервере,
+/// иначе из сети, а если у сайта значка нет — по политике `faviconFallback`.
+/// В кэш попадают только значки самих сайтов. Для приватных окон кэш только
+/// читается, чтобы на диске не оставалось посещённых сайтов
+pub async fn get_favicon(app: &AppHandle, page_url: &Url, private: bool) -> Result<Favicon, LimniError> {
+    let key = cache_key(page_url).ok_or_else(|| LimniError::unsupported_scheme(page_url.as_str()))?;
+    let persist = !private;
+    let cache = app.state::<FaviconCacheState>();
+    let now = now_millis();
+
+    let cached = cache.lookup(&key);
+    if let Some((entry, favicon)) = &cached {
+        if entry.is_fresh(now) {
+            return Ok(favicon.clone());
+        }
+        match revalidate(app, entry).await {
+            Revalidation::NotModified => {
+                if persist {
+                    cache.touch(&key, now);
+                }
+                return Ok(favicon.clone());
+            }
+            Revalidation::Updated(icon) => {
+                if persist {
+                    cache.store(&key, &icon, now);
+                }
+                return Ok(icon.favicon);
+            }
+            Revalidation::Unreachable => return Ok(favicon.clone()),
+            Revalidation::Gone => {}
+        }
+    }
+
+    match resolve_favicon(app, page_url).await {
+        Ok(icon) => {
+            if persist {
+                cache.store(&key, &icon, now);
+            }
+            Ok(icon.favicon)
+        }
+        Err(e) => match cached {
+            // Пусть лучше останется старый значок, чем никакого
+            Some((_, favicon)) => Ok(favicon),
+            None => {
+                println!("🦀 Rust: No favicon of its own for {}: {}", page_url, e);
+                fallback_favicon(app, page_url, private).await
+            }
+        },
+    }
+}
+
+/// Значок сайта из кэша без обращения к сети, даже устаревший
+pub fn cached_favicon(app: &AppHandle, url: &str) -> Option<String> {
+    let key = cache_key(&Url::parse(url).ok()?)?;
+    app.state::<FaviconCacheState>().lookup(&key).map(|(_, favicon)| favicon.to_data_url())
+}
+
+/// Команда для получения фавиконки через бэкенд в виде data URL
+pub async fn fetch_favicon_backend(app: &AppHandle, url: String, private: bool) -> Result<String, LimniError> {
+    let page_url = Url::parse(&url).map_err(|e| LimniError::invalid_url(&url, e))?;
+    if !matches!(page_url.scheme(), "http" | "https") {
+        return Err(LimniError::unsupported_scheme(url));
+    }
+    get_favicon(app, &page_url, private).await.map(|favicon| favicon.to_data_url())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use url::Url;
+use crate::error::LimniError;
+use crate::storage::{data_dir, read_json, write_json_atomic};
+use super::{Favicon, ResolvedIcon};
+
+/// Поддиректория кэша значков в директории данных профиля
+const CACHE_DIR: &str = "favicons";
+
+/// Индекс кэша: какой значок у какого сайта
+const INDEX_FILE: &str = "index.json";
+
+/// Сколько значок считается свежим и отдаётся без обращения к серверу
+pub const FAVICON_TTL_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Сколько сайтов помнит кэш (давно не проверенные вытесняются)
+const MAX_CACHE_ENTRIES: usize = 2000;
+
+/// Ключ кэша: хост страницы, с портом, если он не стандартный
+pub fn cache_key(url: &Url) -> Option<String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.to_ascii_lowercase();
+    Some(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+    })
+}
+
+/// SHA-256 содержимого значка. Он же имя файла, поэтому одинаковые значки
+/// разных сайтов хранятся один раз
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Имя файла из индекса: только хэш, чтобы индекс не мог указать за пределы кэша
+fn is_valid_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Запись о значке сайта
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub hash: String,
+    /// Адрес, с которого загружен значок (по нему идёт проверка на сервере)
+    pub icon_url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Когда значок последний раз загружен или подтверждён сервером
+    pub checked_at: u64,
+}
+
+impl CacheEntry {
+    pub fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.checked_at) < FAVICON_TTL_MS
+    }
+}
+
+/// Формат индекса на диске
+#[derive(Default, Serialize, Deserialize)]
+struct CacheIndex {
+    entries: HashMap<String, CacheEntry>,
+}
+
+/// Кэш значков: индекс сайтов и файлы значков, названные по хэшу содержимого
+#[derive(Default)]
+pub struct FaviconCache {
+    dir: Option<PathBuf>,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl FaviconCache {
+    /// Загружает индекс (пустой кэш, если индекса нет или он повреждён)
+    pub fn load(dir: PathBuf) -> Self {
+        let index: CacheIndex = read_json(&dir.join(INDEX_FILE)).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load favicon cache, starting empty: {}", e);
+            CacheIndex::default()
+        });
+        let entries = index.entries.into_iter()
+            .filter(|(_, entry)| is_valid_hash(&entry.hash))
+            .collect();
+        Self { dir: Some(dir), entries }
+    }
+
+    fn icon_path(&self, hash: &str) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(hash))
+    }
+
+    /// Значок сайта вместе с его записью. Пропавший или испорченный файл считается промахом
+    pub fn get(&self, key: &str) -> Option<(CacheEntry, Favicon)> {
+        let entry = self.entries.get(key)?;
+        let bytes = fs::read(self.icon_path(&entry.hash)?).ok()?;
+        let favicon = Favicon::from_bytes(bytes)?;
+        Some((entry.clone(), favicon))
+    }
+
+    /// Сохраняет значок сайта. Файл с таким содержимым пишется только один раз
+    pub fn insert(&mut self, key: &str, icon: &ResolvedIcon, now: u64) -> Result<(), LimniError> {
+        let Some(dir) = self.dir.clone() else {
+            return Ok(());
+        };
+        let hash = content_hash(&icon.favicon.bytes);
+        let path = dir.join(&hash);
+        if !path.exists() {
+            fs::create_dir_all(&dir)
+                .map_err(|e| LimniError::io(format!("Failed to create {}: {}", dir.display(), e)))?;
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, &icon.favicon.bytes)
+                .map_err(|e| LimniError::io(format!("Failed to write {}: {}", tmp_path.display(), e)))?;
+            fs::rename(&tmp_path, &path)
+                .map_err(|e| LimniError::io(format!("Failed to replace {}: {}", path.display(), e)))?;
+        }
+
+        let entry = CacheEntry {
+            hash,
+            icon_url: icon.url.to_string(),
+            etag: icon.etag.clone(),
+            last_modified: icon.last_modified.clone(),
+            checked_at: now,
+        };
+        let mut released: Vec<String> = self.entries.insert(key.to_string(), entry)
+            .map(|old| old.hash)
+            .into_iter()
+            .collect();
+        released.extend(self.evict());
+        self.remove_unused(released);
+        self.save()
+    }
+
+    /// Отмечает, что сервер подтвердил значок
+    pub fn touch(&mut self, key: &str, now: u64) -> Result<(), LimniError> {
+        match self.entries.get_mut(key) {
+            Some(entry) => entry.checked_at = now,
+            None => return Ok(()),
+        }
+        self.save()
+    }
+
+    /// Удаляет все значки и индекс
+    pub fn clear(&mut self) -> Result<(), LimniError> {
+        self.entries.clear();
+        match &self.dir {
+            Some(dir) if dir.exists() => fs::remove_dir_all(dir)
+                .map_err(|e| LimniError::io(format!("Failed to remove {}: {}", dir.display(), e))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Вытесняет давно не проверенные записи сверх лимита, возвращает их хэши
+    fn evict(&mut self) -> Vec<String> {
+        let mut released = Vec::new();
+        while self.entries.len() > MAX_CACHE_ENTRIES {
+            let Some(oldest) = self.entries.iter()
+                .min_by_key(|(_, entry)| entry.checked_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                released.push(entry.hash);
+            }
+        }
+        released
+    }
+
+    /// Удаляет файлы значков, на которые больше не ссылается ни один сайт
+    fn remove_unused(&self, hashes: Vec<String>) {
+        for hash in hashes {
+            if self.entries.values().any(|entry| entry.hash == hash) {
+                continue;
+            }
+            if let Some(path) = self.icon_path(&hash) {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), LimniError> {
+        match &self.dir {
+            Some(dir) => write_json_atomic(&dir.join(INDEX_FILE), &CacheIndex { entries: self.entries.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Кэш значков текущего профиля
+#[derive(Default)]
+pub struct FaviconCacheState {
+    cache: Mutex<FaviconCache>,
+}
+
+impl FaviconCacheState {
+    /// Загружает кэш из директории данных профиля
+    pub fn load(app: &AppHandle) -> Self {
+        let cache = match data_dir(app) {
+            Ok(dir) => FaviconCache::load(dir.join(CACHE_DIR)),
+            Err(e) => {
+                println!("🦀 Rust: Favicon cache is disabled: {}", e);
+                FaviconCache::default()
+            }
+        };
+        Self { cache: Mutex::new(cache) }
+    }
+
+    pub fn lookup(&self, key: &str) -> Option<(CacheEntry, Favicon)> {
+        self.cache.lock().unwrap().get(key)
+    }
+
+    pub fn store(&self, key: &str, icon: &ResolvedIcon, now: u64) {
+        if let Err(e) = self.cache.lock().unwrap().insert(key, icon, now) {
+            println!("🦀 Rust: Failed to cache favicon for {}: {}", key, e);
+        }
+    }
+
+    pub fn touch(&self, key: &str, now: u64) {
+        if let Err(e) = self.cache.lock().unwrap().touch(key, now) {
+            println!("🦀 Rust: Failed to update favicon cache for {}: {}", key, e);
+        }
+    }
+
+    pub fn clear(&self) -> Result<(), LimniError> {
+        self.cache.lock().unwrap().clear()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,98 @@
+use super::*;
+use crate::utils::now_millis;
+
+const GIF: &[u8] = b"GIF89a\x01\0\x01\0";
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+fn temp_cache_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("limni_favicon_cache_test_{}_{}", name, now_millis()))
+}
+
+fn icon(url: &str, bytes: &[u8], etag: Option<&str>) -> ResolvedIcon {
+    ResolvedIcon {
+        favicon: Favicon::from_bytes(bytes.to_vec()).unwrap(),
+        url: Url::parse(url).unwrap(),
+        etag: etag.map(str::to_string),
+        last_modified: None,
+    }
+}
+
+fn icon_files(dir: &std::path::Path) -> usize {
+    fs::read_dir(dir).unwrap()
+        .filter(|entry| entry.as_ref().unwrap().file_name() != INDEX_FILE)
+        .count()
+}
+
+#[test]
+fn test_cache_key_uses_host_and_non_default_port() {
+    let key = |url: &str| cache_key(&Url::parse(url).unwrap());
+    assert_eq!(key("https://Example.COM/page?q=1").as_deref(), Some("example.com"));
+    assert_eq!(key("http://example.com:80/").as_deref(), Some("example.com"));
+    assert_eq!(key("http://localhost:5173/app").as_deref(), Some("localhost:5173"));
+    assert_eq!(key("file:///tmp/index.html"), None);
+}
+
+#[test]
+fn test_entry_freshness_follows_ttl() {
+    let entry = CacheEntry {
+        hash: content_hash(GIF),
+        icon_url: "https://example.com/favicon.ico".to_string(),
+        etag: None,
+        last_modified: None,
+        checked_at: 1_000,
+    };
+    assert!(entry.is_fresh(1_000 + FAVICON_TTL_MS - 1));
+    assert!(!entry.is_fresh(1_000 + FAVICON_TTL_MS));
+}
+
+#[test]
+fn test_identical_icons_are_stored_once_and_survive_reload() {
+    let dir = temp_cache_dir("dedupe");
+    let mut cache = FaviconCache::load(dir.clone());
+    cache.insert("a.example.com", &icon("https://a.example.com/favicon.ico", GIF, Some("\"v1\"")), 10).unwrap();
+    cache.insert("b.example.com", &icon("https://b.example.com/icon.gif", GIF, None), 20).unwrap();
+    assert_eq!(icon_files(&dir), 1);
+
+    let reloaded = FaviconCache::load(dir.clone());
+    let (entry, favicon) = reloaded.get("a.example.com").unwrap();
+    assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+    assert_eq!(entry.checked_at, 10);
+    assert_eq!(favicon.bytes, GIF);
+    assert!(reloaded.get("c.example.com").is_none());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_replaced_icon_file_is_removed_when_unused() {
+    let dir = temp_cache_dir("replace");
+    let mut cache = FaviconCache::load(dir.clone());
+    cache.insert("example.com", &icon("https://example.com/favicon.ico", GIF, None), 10).unwrap();
+    cache.insert("example.com", &icon("https://example.com/icon.png", PNG, None), 20).unwrap();
+
+    assert_eq!(icon_files(&dir), 1);
+    assert!(dir.join(content_hash(PNG)).exists());
+    assert_eq!(cache.get("example.com").unwrap().1.mime, "image/png");
+
+    cache.touch("example.com", 30).unwrap();
+    assert_eq!(FaviconCache::load(dir.clone()).get("example.com").unwrap().0.checked_at, 30);
+
+    cache.clear().unwrap();
+    assert_eq!(cache.len(), 0);
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_index_entries_with_invalid_hash_are_ignored() {
+    let dir = temp_cache_dir("invalid");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join(INDEX_FILE),
+        r#"{"entries": {"example.com": {"hash": "../../settings.json", "iconUrl": "https://example.com/favicon.ico", "etag": null, "lastModified": null, "checkedAt": 1}}}"#,
+    ).unwrap();
+
+    let cache = FaviconCache::load(dir.clone());
+    assert_eq!(cache.len(), 0);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,134 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use sha2::{Digest, Sha256};
+use url::{Host, Url};
+use crate::error::LimniError;
+use super::Favicon;
+
+/// Значение настройки `faviconFallback`
+pub const FALLBACK_NONE: &str = "none";
+pub const FALLBACK_SERVICE: &str = "service";
+pub const FALLBACK_LETTER: &str = "letter";
+
+/// Подстановка хоста в адресе сервиса значков
+pub const HOST_PLACEHOLDER: &str = "{host}";
+
+/// Сервис значков, который предлагается по умолчанию для `service`
+pub const DEFAULT_ICON_SERVICE: &str = "https://icons.duckduckgo.com/ip3/{host}.ico";
+
+/// Что показать, если у сайта не нашлось своего значка
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FaviconFallback {
+    /// Без значка
+    Disabled,
+    /// Значок из внешнего сервиса по шаблону адреса с `{host}`
+    Service(String),
+    /// Значок с первой буквой сайта, рисуется локально
+    Letter,
+}
+
+impl FaviconFallback {
+    /// Политика из настроек. Неизвестное значение — локальный значок, он ничего не отправляет в сеть
+    pub fn from_settings(fallback: &str, service_url: &str) -> Self {
+        match fallback {
+            FALLBACK_NONE => Self::Disabled,
+            FALLBACK_SERVICE => Self::Service(service_url.trim().to_string()),
+            _ => Self::Letter,
+        }
+    }
+}
+
+/// Проверяет настройки политики до сохранения
+pub fn validate_settings(fallback: &str, service_url: &str) -> Result<(), LimniError> {
+    if ![FALLBACK_NONE, FALLBACK_SERVICE, FALLBACK_LETTER].contains(&fallback) {
+        return Err(LimniError::invalid_argument(format!("Unknown favicon fallback: {}", fallback)));
+    }
+    if fallback == FALLBACK_SERVICE {
+        service_icon_url(service_url, "example.com")?;
+    }
+    Ok(())
+}
+
+/// Адрес значка в сервисе. Шаблон должен содержать `{host}` и давать http(s) адрес
+pub fn service_icon_url(template: &str, host: &str) -> Result<Url, LimniError> {
+    let template = template.trim();
+    if !template.contains(HOST_PLACEHOLDER) {
+        return Err(LimniError::invalid_argument(format!("Icon service URL must contain {}", HOST_PLACEHOLDER)));
+    }
+    let url = template.replace(HOST_PLACEHOLDER, host);
+    let parsed = Url::parse(&url).map_err(|e| LimniError::invalid_url(&url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(LimniError::unsupported_scheme(url));
+    }
+    Ok(parsed)
+}
+
+/// Доменные зоны, которые не разрешаются в публичном DNS
+const LOCAL_SUFFIXES: &[&str] = &[".localhost", ".local", ".internal", ".lan", ".home.arpa"];
+
+/// Можно ли сообщать хост сайта внешнему сервису. Локальные имена,
+/// имена без точки (интранет) и адреса частных сетей — нельзя
+pub fn is_public_host(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain.contains('.')
+                && domain != "localhost"
+                && !LOCAL_SUFFIXES.iter().any(|suffix| domain.ends_with(suffix))
+        }
+        Some(Host::Ipv4(ip)) => is_public_ipv4(ip),
+        Some(Host::Ipv6(ip)) => is_public_ipv6(ip),
+        None => false,
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    // 100.64.0.0/10 — адреса операторского NAT
+    let shared = a == 100 && (64..128).contains(&b);
+    !(ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified() || ip.is_broadcast() || shared)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(ipv4);
+    }
+    let first = ip.segments()[0];
+    let unique_local = first & 0xfe00 == 0xfc00;
+    let link_local = first & 0xffc0 == 0xfe80;
+    !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+}
+
+/// Можно ли для этой страницы обращаться к внешнему сервису значков
+pub fn may_use_service(url: &Url, private: bool) -> bool {
+    !private && is_public_host(url)
+}
+
+/// Цвета фона буквенных значков
+const LETTER_COLORS: &[&str] = &[
+    "#e8453c", "#f29900", "#188038", "#1a73e8", "#9334e6", "#d01884", "#12858a", "#5f6368",
+];
+
+/// Буква для значка: первая буква или цифра хоста без `www.`
+fn icon_letter(host: &str) -> char {
+    host.trim_start_matches("www.")
+        .chars()
+        .find(|c| c.is_alphanumeric())
+        .map(|c| c.to_uppercase().next().unwrap_or(c))
+        .unwrap_or('?')
+}
+
+/// Рисует SVG значок с первой буквой сайта. Цвет зависит от хоста,
+/// поэтому у одного сайта он всегда один и тот же
+pub fn letter_icon(host: &str) -> Favicon {
+    let host = host.to_lowercase();
+    let color = LETTER_COLORS[Sha256::digest(host.as_bytes())[0] as usize % LETTER_COLORS.len()];
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="32" height="32" viewBox="0 0 32 32"><rect width="32" height="32" rx="6" fill="{color}"/><text x="16" y="22" text-anchor="middle" font-family="system-ui, -apple-system, 'Segoe UI', sans-serif" font-size="18" font-weight="600" fill="#ffffff">{letter}</text></svg>"##,
+        color = color,
+        letter = icon_letter(&host),
+    );
+    Favicon { mime: "image/svg+xml", bytes: svg.into_bytes() }
+}
+
+#[cfg(test)]
+mod tests;
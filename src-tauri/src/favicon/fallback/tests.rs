@@ -0,0 +1,78 @@
+use super::*;
+use crate::favicon::sniff_image;
+
+fn public(url: &str) -> bool {
+    is_public_host(&Url::parse(url).unwrap())
+}
+
+#[test]
+fn test_local_and_private_hosts_are_not_public() {
+    for url in [
+        "http://localhost:3000/",
+        "http://app.localhost/",
+        "http://printer.local/",
+        "http://wiki/",
+        "http://nas.home.arpa/",
+        "http://10.1.2.3/",
+        "http://172.16.0.1/",
+        "http://192.168.1.1/",
+        "http://127.0.0.1:8080/",
+        "http://169.254.1.1/",
+        "http://100.64.0.1/",
+        "http://[::1]/",
+        "http://[fd12:3456::1]/",
+        "http://[fe80::1]/",
+        "http://[::ffff:192.168.0.1]/",
+    ] {
+        assert!(!public(url), "{} must not be sent to an icon service", url);
+    }
+}
+
+#[test]
+fn test_internet_hosts_are_public() {
+    assert!(public("https://example.com/"));
+    assert!(public("https://www.example.co.uk/page"));
+    assert!(public("http://8.8.8.8/"));
+    assert!(public("http://172.32.0.1/"));
+    assert!(public("http://[2001:4860::8888]/"));
+}
+
+#[test]
+fn test_private_windows_never_use_the_service() {
+    let url = Url::parse("https://example.com/").unwrap();
+    assert!(may_use_service(&url, false));
+    assert!(!may_use_service(&url, true));
+}
+
+#[test]
+fn test_fallback_from_settings() {
+    assert_eq!(FaviconFallback::from_settings("none", DEFAULT_ICON_SERVICE), FaviconFallback::Disabled);
+    assert_eq!(FaviconFallback::from_settings("letter", DEFAULT_ICON_SERVICE), FaviconFallback::Letter);
+    assert_eq!(
+        FaviconFallback::from_settings("service", " https://icons.example/{host} "),
+        FaviconFallback::Service("https://icons.example/{host}".to_string())
+    );
+    assert_eq!(FaviconFallback::from_settings("bogus", DEFAULT_ICON_SERVICE), FaviconFallback::Letter);
+}
+
+#[test]
+fn test_service_url_template_is_validated() {
+    let url = service_icon_url(DEFAULT_ICON_SERVICE, "example.com").unwrap();
+    assert_eq!(url.as_str(), "https://icons.duckduckgo.com/ip3/example.com.ico");
+
+    assert_eq!(service_icon_url("https://icons.example/favicon.ico", "example.com").unwrap_err().code(), "INVALID_ARGUMENT");
+    assert_eq!(service_icon_url("file:///icons/{host}.ico", "example.com").unwrap_err().code(), "UNSUPPORTED_SCHEME");
+    assert!(validate_settings(FALLBACK_SERVICE, "not a url {host}").is_err());
+    assert!(validate_settings(FALLBACK_LETTER, "").is_ok());
+    assert!(validate_settings("sometimes", DEFAULT_ICON_SERVICE).is_err());
+}
+
+#[test]
+fn test_letter_icon_is_a_stable_svg() {
+    let icon = letter_icon("www.Example.com");
+    assert_eq!(sniff_image(&icon.bytes), Some("image/svg+xml"));
+    assert!(String::from_utf8_lossy(&icon.bytes).contains(">E</text>"));
+    assert_eq!(icon, letter_icon("www.example.com"));
+    assert_eq!(icon_letter("яндекс.рф"), 'Я');
+    assert_eq!(icon_letter("-.-"), '?');
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+fn page() -> Url {
+    Url::parse("https://example.com/blog/post").unwrap()
+}
+
+#[test]
+fn test_parse_page_icons_resolves_links_and_manifest() {
+    let html = r#"<html><head>
+        <link rel="stylesheet" href="/style.css">
+        <LINK REL="shortcut icon" href="favicon-16.png" sizes="16x16">
+        <link rel='apple-touch-icon' href='/apple.png'>
+        <link rel="icon" type="image/svg+xml" href="/icon.svg?v=1&amp;t=2">
+        <link rel="mask-icon" href="/mask.svg">
+        <link rel="manifest" href="/site.webmanifest">
+    </head><body></body></html>"#;
+    let icons = parse_page_icons(html, &page());
+
+    let urls: Vec<&str> = icons.icons.iter().map(|icon| icon.url.as_str()).collect();
+    assert_eq!(urls, vec![
+        "https://example.com/blog/favicon-16.png",
+        "https://example.com/apple.png",
+        "https://example.com/icon.svg?v=1&t=2",
+    ]);
+    assert_eq!(icons.icons[0].sizes, IconSizes::Fixed(vec![16]));
+    assert_eq!(icons.icons[1].sizes, IconSizes::Fixed(vec![180]));
+    assert_eq!(icons.manifest.unwrap().as_str(), "https://example.com/site.webmanifest");
+}
+
+#[test]
+fn test_parse_page_icons_uses_base_href() {
+    let html = r#"<head><link rel="icon" href="icon.png"><base href="https://cdn.example.net/assets/"></head>"#;
+    let icons = parse_page_icons(html, &page());
+    assert_eq!(icons.icons[0].url.as_str(), "https://cdn.example.net/assets/icon.png");
+}
+
+#[test]
+fn test_parse_sizes() {
+    assert_eq!(parse_sizes(Some("16x16 32X32")), IconSizes::Fixed(vec![16, 32]));
+    assert_eq!(parse_sizes(Some("any")), IconSizes::Any);
+    assert_eq!(parse_sizes(Some("48x64")), IconSizes::Fixed(vec![64]));
+    assert_eq!(parse_sizes(Some("big")), IconSizes::Unknown);
+    assert_eq!(parse_sizes(None), IconSizes::Unknown);
+}
+
+#[test]
+fn test_rank_candidates_prefers_svg_then_closest_larger_size() {
+    let icon = |path: &str, sizes: IconSizes, mime: Option<&str>| {
+        IconCandidate::new(page().join(path).unwrap(), IconSource::Link, sizes, mime.map(str::to_string))
+    };
+    let mut dark = icon("/dark.svg", IconSizes::Any, Some("image/svg+xml"));
+    dark.media = true;
+    let ranked = rank_candidates(vec![
+        default_icon(&page()).unwrap(),
+        icon("/16.png", IconSizes::Fixed(vec![16]), None),
+        icon("/unknown.ico", IconSizes::Unknown, None),
+        icon("/192.png", IconSizes::Fixed(vec![192]), None),
+        dark,
+        icon("/48.png", IconSizes::Fixed(vec![48]), None),
+        icon("/icon.svg", IconSizes::Unknown, None),
+        icon("/48.png", IconSizes::Fixed(vec![48]), None),
+    ]);
+
+    let paths: Vec<&str> = ranked.iter().map(|icon| icon.url.path()).collect();
+    assert_eq!(paths, vec!["/icon.svg", "/48.png", "/192.png", "/16.png", "/unknown.ico", "/favicon.ico", "/dark.svg"]);
+}
+
+#[test]
+fn test_parse_manifest_icons_skips_maskable_only_icons() {
+    let manifest = r#"{"name": "App", "icons": [
+        {"src": "icons/192.png", "sizes": "192x192", "type": "image/png"},
+        {"src": "icons/mask.png", "sizes": "512x512", "purpose": "maskable"},
+        {"src": "icons/any.png", "sizes": "512x512", "purpose": "any maskable"}
+    ]}"#;
+    let manifest_url = Url::parse("https://example.com/app/manifest.json").unwrap();
+    let icons = parse_manifest_icons(manifest, &manifest_url);
+
+    let urls: Vec<&str> = icons.iter().map(|icon| icon.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://example.com/app/icons/192.png", "https://example.com/app/icons/any.png"]);
+    assert!(icons.iter().all(|icon| icon.source == IconSource::Manifest));
+    assert!(parse_manifest_icons("not json", &manifest_url).is_empty());
+}
+
+#[test]
+fn test_sniff_image_detects_formats_by_content() {
+    assert_eq!(sniff_image(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+    assert_eq!(sniff_image(&[0, 0, 1, 0, 1, 0, 16, 16]), Some("image/x-icon"));
+    assert_eq!(sniff_image(b"GIF89a\x01\0\x01\0"), Some("image/gif"));
+    assert_eq!(sniff_image(b"\xff\xd8\xff\xe0"), Some("image/jpeg"));
+    assert_eq!(sniff_image(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+    assert_eq!(
+        sniff_image(b"\xef\xbb\xbf<?xml version=\"1.0\"?>\n<!-- logo -->\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+        Some("image/svg+xml")
+    );
+}
+
+#[test]
+fn test_sniff_image_rejects_html_and_empty_bodies() {
+    assert_eq!(sniff_image(b""), None);
+    assert_eq!(sniff_image(b"<!DOCTYPE html><html><body><svg></svg></body></html>"), None);
+    assert_eq!(sniff_image(b"Not Found"), None);
+    assert_eq!(sniff_image(&[0, 0, 1, 0, 0, 0]), None);
+}
+
+#[test]
+fn test_favicon_data_url_uses_sniffed_type() {
+    let favicon = Favicon::from_bytes(b"GIF89a\x01\0\x01\0".to_vec()).unwrap();
+    assert_eq!(favicon.to_data_url(), "data:image/gif;base64,R0lGODlhAQABAA==");
+
+    let inline = Url::parse("data:image/gif;base64,R0lGODlhAQABAA==").unwrap();
+    assert_eq!(decode_data_url(&inline).and_then(Favicon::from_bytes), Some(favicon));
+}
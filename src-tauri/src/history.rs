@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use crate::error::LimniError;
+use crate::storage::{data_dir, read_json_with_backup, write_json_with_backup};
+use crate::utils::now_millis;
+use crate::private::is_private_tab;
+
+/// Имя файла истории в директории данных приложения
+const HISTORY_FILE: &str = "history.json";
+
+/// Максимальное количество хранимых записей (самые старые вытесняются)
+const MAX_HISTORY_ENTRIES: usize = 10_000;
+
+/// Интервал периодического сохранения истории
+const AUTOSAVE_INTERVAL_SECS: u64 = 5;
+
+/// Запись истории посещений (одна на URL)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub visited_at: u64, // время последнего посещения, мс с начала эпохи
+    pub visit_count: u32,
+    pub tab_id: Option<String>,
+}
+
+/// Формат файла истории на диске
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryFile {
+    entries: Vec<HistoryEntry>,
+}
+
+/// Хранилище истории: записи упорядочены от новых к старым
+#[derive(Default)]
+pub struct HistoryStore {
+    path: Option<PathBuf>,
+    entries: Vec<HistoryEntry>,
+    /// Счётчик для id записей: время не уникально в пределах миллисекунды
+    next_id: u64,
+}
+
+impl HistoryStore {
+    /// Загружает историю из файла или его резервной копии (пустая история, если нет обоих)
+    pub fn load(path: PathBuf) -> Self {
+        let file: HistoryFile = read_json_with_backup(&path).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load history, starting empty: {}", e);
+            HistoryFile::default()
+        });
+
+        let mut entries = file.entries;
+        entries.sort_by_key(|e| std::cmp::Reverse(e.visited_at));
+
+        Self { path: Some(path), entries, next_id: 0 }
+    }
+
+    /// Сохраняет историю на диск
+    pub fn save(&self) -> Result<(), LimniError> {
+        match &self.path {
+            Some(path) => write_json_with_backup(path, &HistoryFile { entries: self.entries.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    /// Регистрирует посещение URL: увеличивает счётчик существующей записи или создаёт новую
+    pub fn record_visit(&mut self, url: &str, tab_id: &str, now: u64) {
+        let entry = match self.entries.iter().position(|e| e.url == url) {
+            Some(index) => {
+                let mut entry = self.entries.remove(index);
+                entry.visited_at = now;
+                entry.visit_count += 1;
+                entry.tab_id = Some(tab_id.to_string());
+                entry
+            }
+            None => HistoryEntry {
+                id: {
+                    self.next_id += 1;
+                    format!("h_{}_{}", now, self.next_id)
+                },
+                url: url.to_string(),
+                title: String::new(),
+                visited_at: now,
+                visit_count: 1,
+                tab_id: Some(tab_id.to_string()),
+            },
+        };
+
+        self.entries.insert(0, entry);
+        self.entries.truncate(MAX_HISTORY_ENTRIES);
+    }
+
+    /// Обновляет title последней записи, посещённой во вкладке
+    pub fn update_title(&mut self, tab_id: &str, title: &str) -> bool {
+        let title = title.trim();
+        if title.is_empty() {
+            return false;
+        }
+
+        match self.entries.iter_mut().find(|e| e.tab_id.as_deref() == Some(tab_id)) {
+            Some(entry) if entry.title != title => {
+                entry.title = title.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Возвращает страницу истории от новых записей к старым
+    pub fn list(&self, offset: usize, limit: usize) -> Vec<HistoryEntry> {
+        self.entries.iter().skip(offset).take(limit).cloned().collect()
+    }
+
+    /// Ищет записи по подстроке в URL или title (без учёта регистра)
+    pub fn search(&self, query: &str, limit: usize) -> Vec<HistoryEntry> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return self.list(0, limit);
+        }
+
+        self.entries
+            .iter()
+            .filter(|e| e.url.to_lowercase().contains(&query) || e.title.to_lowercase().contains(&query))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Удаляет запись по id
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        self.entries.len() != before
+    }
+
+    /// Удаляет записи, посещённые в промежутке [from, to]. Границы по умолчанию открыты
+    pub fn clear_range(&mut self, from: Option<u64>, to: Option<u64>) -> usize {
+        let from = from.unwrap_or(0);
+        let to = to.unwrap_or(u64::MAX);
+        let before = self.entries.len();
+        self.entries.retain(|e| e.visited_at < from || e.visited_at > to);
+        before - self.entries.len()
+    }
+}
+
+/// Состояние истории, разделяемое между командами. Посещения и title
+/// меняют только память, на диск история пишется периодически и при выходе
+#[derive(Default)]
+pub struct HistoryState {
+    pub store: Mutex<HistoryStore>,
+    dirty: AtomicBool,
+}
+
+impl HistoryState {
+    /// Загружает историю из директории данных приложения
+    pub fn load(app: &AppHandle) -> Self {
+        let store = match data_dir(app) {
+            Ok(dir) => HistoryStore::load(dir.join(HISTORY_FILE)),
+            Err(e) => {
+                println!("🦀 Rust: History will not be persisted: {}", e);
+                HistoryStore::default()
+            }
+        };
+
+        Self { store: Mutex::new(store), dirty: AtomicBool::new(false) }
+    }
+
+    /// Применяет изменение к истории и, если оно что-то поменяло, помечает её для сохранения
+    pub fn update<F: FnOnce(&mut HistoryStore) -> bool>(&self, f: F) {
+        let mut store = self.store.lock().unwrap();
+        if f(&mut store) {
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Сохраняет историю, если с прошлого сохранения были изменения
+    pub fn save_if_dirty(&self) -> Result<(), LimniError> {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let store = self.store.lock().unwrap();
+        store.save().inspect_err(|_| {
+            // Повторим попытку при следующем сохранении
+            self.dirty.store(true, Ordering::SeqCst);
+        })
+    }
+}
+
+/// Историю ведём только для обычных веб-страниц
+fn is_recordable(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Записывает посещение в историю. Для вкладок приватных окон не вызывается
+pub fn record_visit(app: &AppHandle, url: &str, tab_id: &str) {
+    if !is_recordable(url) {
+        return;
+    }
+    if let Some(state) = app.try_state::<HistoryState>() {
+        state.update(|store| {
+            store.record_visit(url, tab_id, now_millis());
+            true
+        });
+    }
+}
+
+/// Обновляет title текущей записи вкладки
+pub fn record_title(app: &AppHandle, tab_id: &str, title: &str) {
+    let app = app.clone();
+    let tab_id = tab_id.to_string();
+    let title = title.to_string();
+    tauri::async_runtime::spawn(async move {
+        if is_private_tab(&app, &tab_id).await {
+            return;
+        }
+
+        if let Some(state) = app.try_state::<HistoryState>() {
+            state.update(|store| store.update_title(&tab_id, &title));
+        }
+    });
+}
+
+/// Сохраняет историю немедленно (например, при выходе из приложения)
+pub fn save_now(app: &AppHandle) {
+    if let Some(state) = app.try_state::<HistoryState>() {
+        if let Err(e) = state.save_if_dirty() {
+            println!("🦀 Rust: Failed to save history: {}", e);
+        }
+    }
+}
+
+/// Запускает периодическое сохранение истории
+pub fn start_autosave(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(AUTOSAVE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let app_for_save = app.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || save_now(&app_for_save)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests;
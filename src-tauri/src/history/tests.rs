@@ -0,0 +1,94 @@
+use super::*;
+
+#[test]
+fn test_record_visit_increments_count() {
+    let mut store = HistoryStore::default();
+    store.record_visit("https://example.com/", "tab_1", 100);
+    store.record_visit("https://rust-lang.org/", "tab_1", 200);
+    store.record_visit("https://example.com/", "tab_2", 300);
+
+    let entries = store.list(0, 10);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].url, "https://example.com/");
+    assert_eq!(entries[0].visit_count, 2);
+    assert_eq!(entries[0].visited_at, 300);
+    assert_eq!(entries[0].tab_id.as_deref(), Some("tab_2"));
+}
+
+#[test]
+fn test_entry_ids_stay_unique_after_remove() {
+    let mut store = HistoryStore::default();
+    store.record_visit("https://a.com/", "tab_1", 100);
+    store.record_visit("https://b.com/", "tab_1", 100);
+    let id = store.list(0, 1)[0].id.clone();
+    assert!(store.remove(&id));
+    store.record_visit("https://c.com/", "tab_1", 100);
+
+    let entries = store.list(0, 10);
+    assert_ne!(entries[0].id, entries[1].id);
+    assert!(store.remove(&entries[0].id));
+    assert_eq!(store.list(0, 10).len(), 1);
+}
+
+#[test]
+fn test_update_title_targets_latest_tab_entry() {
+    let mut store = HistoryStore::default();
+    store.record_visit("https://example.com/", "tab_1", 100);
+    store.record_visit("https://example.com/docs", "tab_1", 200);
+
+    assert!(store.update_title("tab_1", "  Docs  "));
+    assert!(!store.update_title("tab_1", "Docs"));
+    assert!(!store.update_title("tab_2", "Other"));
+
+    let entries = store.list(0, 10);
+    assert_eq!(entries[0].title, "Docs");
+    assert_eq!(entries[1].title, "");
+}
+
+#[test]
+fn test_search_matches_url_and_title() {
+    let mut store = HistoryStore::default();
+    store.record_visit("https://example.com/", "tab_1", 100);
+    store.update_title("tab_1", "Example Domain");
+    store.record_visit("https://github.com/", "tab_2", 200);
+
+    assert_eq!(store.search("EXAMPLE", 10).len(), 1);
+    assert_eq!(store.search("github", 10)[0].url, "https://github.com/");
+    assert_eq!(store.search("", 10).len(), 2);
+}
+
+#[test]
+fn test_remove_and_clear_range() {
+    let mut store = HistoryStore::default();
+    store.record_visit("https://a.com/", "tab_1", 100);
+    store.record_visit("https://b.com/", "tab_1", 200);
+    store.record_visit("https://c.com/", "tab_1", 300);
+
+    let id = store.list(0, 1)[0].id.clone();
+    assert!(store.remove(&id));
+    assert!(!store.remove(&id));
+
+    assert_eq!(store.clear_range(Some(150), None), 1);
+    assert_eq!(store.list(0, 10)[0].url, "https://a.com/");
+    assert_eq!(store.clear_range(None, None), 1);
+}
+
+#[test]
+fn test_history_is_written_only_when_changed() {
+    let dir = std::env::temp_dir().join(format!("limni_history_test_{}", now_millis()));
+    let path = dir.join(HISTORY_FILE);
+    let state = HistoryState { store: Mutex::new(HistoryStore::load(path.clone())), ..HistoryState::default() };
+
+    state.update(|store| store.update_title("tab_1", "Nothing to update"));
+    state.save_if_dirty().unwrap();
+    assert!(!path.exists());
+
+    state.update(|store| {
+        store.record_visit("https://example.com/", "tab_1", 100);
+        true
+    });
+    assert!(!path.exists());
+    state.save_if_dirty().unwrap();
+    assert_eq!(HistoryStore::load(path).list(0, 10).len(), 1);
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use serde::Serialize;
+use tauri::AppHandle;
+use crate::error::LimniError;
+
+/// Значение настройки `language`: язык системы
+pub const LANGUAGE_SYSTEM: &str = "system";
+
+/// Переменные окружения с языком системы, в порядке приоритета (как у gettext)
+const LOCALE_VARIABLES: &[&str] = &["LC_ALL", "LC_MESSAGES", "LANGUAGE", "LANG"];
+
+/// Каталоги сообщений: ключ -> текст с подстановками `{name}`
+const EN_CATALOG: &str = include_str!("../data/locales/en.json");
+const RU_CATALOG: &str = include_str!("../data/locales/ru.json");
+const PT_BR_CATALOG: &str = include_str!("../data/locales/pt-BR.json");
+
+mod system;
+
+static SYSTEM_LOCALE: LazyLock<Locale> = LazyLock::new(|| {
+    locale_from_system(&system::preferred_languages(), |name| std::env::var(name).ok())
+});
+
+static CATALOGS: LazyLock<HashMap<Locale, HashMap<String, String>>> = LazyLock::new(|| {
+    Locale::ALL.iter().map(|&locale| (locale, parse_catalog(locale))).collect()
+});
+
+/// Язык интерфейса, для которого есть каталог сообщений
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    En,
+    Ru,
+    PtBr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Ru, Locale::PtBr];
+
+    /// Язык, на который переводятся недостающие сообщения
+    pub const FALLBACK: Locale = Locale::En;
+
+    /// Тег языка BCP 47
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ru => "ru",
+            Locale::PtBr => "pt-BR",
+        }
+    }
+
+    /// Язык по тегу BCP 47 или POSIX локали: `ru`, `ru_RU.UTF-8`, `pt-BR`, `pt_PT@euro`.
+    /// Португальский без региона тоже получает бразильский каталог
+    pub fn parse(tag: &str) -> Option<Self> {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default().to_ascii_lowercase();
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        match language {
+            "en" => Some(Locale::En),
+            "ru" => Some(Locale::Ru),
+            "pt" => Some(Locale::PtBr),
+            _ => None,
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Locale::En => EN_CATALOG,
+            Locale::Ru => RU_CATALOG,
+            Locale::PtBr => PT_BR_CATALOG,
+        }
+    }
+}
+
+fn parse_catalog(locale: Locale) -> HashMap<String, String> {
+    serde_json::from_str(locale.source()).unwrap_or_else(|e| {
+        println!("🦀 Rust: Invalid {} message catalog: {}", locale.code(), e);
+        HashMap::new()
+    })
+}
+
+/// Язык системы. Определяется один раз за запуск
+pub fn system_locale() -> Locale {
+    *SYSTEM_LOCALE
+}
+
+/// Первый поддерживаемый из языков, заданных в настройках ОС,
+/// иначе язык из переменных окружения
+fn locale_from_system(preferred: &[String], var: impl Fn(&str) -> Option<String>) -> Locale {
+    preferred.iter()
+        .find_map(|tag| Locale::parse(tag))
+        .unwrap_or_else(|| locale_from_env(var))
+}
+
+/// Язык из переменных окружения. `LANGUAGE` может содержать список
+/// через двоеточие, берётся первый поддерживаемый. `C` и `POSIX` — английский
+fn locale_from_env(var: impl Fn(&str) -> Option<String>) -> Locale {
+    LOCALE_VARIABLES.iter()
+        .filter_map(|name| var(name).filter(|value| !value.is_empty()))
+        .find_map(|value| value.split(':').find_map(Locale::parse))
+        .unwrap_or(Locale::FALLBACK)
+}
+
+/// Язык по значению настройки: `system` или тег поддерживаемого языка
+pub fn locale_from_settings(language: &str) -> Locale {
+    match language {
+        LANGUAGE_SYSTEM => system_locale(),
+        language => Locale::parse(language).unwrap_or_else(system_locale),
+    }
+}
+
+/// Проверяет настройку языка до сохранения
+pub fn validate_language(language: &str) -> Result<(), LimniError> {
+    if language == LANGUAGE_SYSTEM || Locale::ALL.iter().any(|locale| locale.code() == language) {
+        Ok(())
+    } else {
+        Err(LimniError::invalid_argument(format!("Unsupported language: {}", language)))
+    }
+}
+
+/// Текущий язык интерфейса с учётом настроек
+pub fn current_locale(app: &AppHandle) -> Locale {
+    locale_from_settings(&crate::settings::current(app).language)
+}
+
+/// Сообщение на заданном языке. Если перевода нет — английское,
+/// если нет и его — сам ключ. `{name}` заменяются значениями из `args`
+pub fn translate(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let message = [locale, Locale::FALLBACK].iter()
+        .find_map(|locale| CATALOGS.get(locale).and_then(|catalog| catalog.get(key)))
+        .map(String::as_str)
+        .unwrap_or(key);
+    args.iter().fold(message.to_string(), |message, (name, value)| {
+        message.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Сообщение на текущем языке интерфейса
+pub fn t(app: &AppHandle, key: &str) -> String {
+    translate(current_locale(app), key, &[])
+}
+
+/// Сообщение на текущем языке интерфейса с подстановками
+pub fn t_args(app: &AppHandle, key: &str, args: &[(&str, &str)]) -> String {
+    translate(current_locale(app), key, args)
+}
+
+/// Язык интерфейса для frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleInfo {
+    pub locale: &'static str,
+    pub system_locale: &'static str,
+    pub available: Vec<&'static str>,
+}
+
+pub fn locale_info(app: &AppHandle) -> LocaleInfo {
+    LocaleInfo {
+        locale: current_locale(app).code(),
+        system_locale: system_locale().code(),
+        available: Locale::ALL.iter().map(|locale| locale.code()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
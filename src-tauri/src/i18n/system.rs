@@ -0,0 +1,64 @@
+//! Предпочитаемые языки интерфейса из настроек ОС. На Windows и у приложений macOS,
+//! запущенных из Finder, переменные `LANG`/`LC_*` обычно не заданы
+
+/// Теги языков в порядке предпочтения пользователя (пустой список, если ОС их не сообщила)
+#[cfg(windows)]
+pub fn preferred_languages() -> Vec<String> {
+    /// Имена языков вида `ru-RU` вместо числовых идентификаторов
+    const MUI_LANGUAGE_NAME: u32 = 0x8;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetUserPreferredUILanguages(flags: u32, num_languages: *mut u32, buffer: *mut u16, buffer_size: *mut u32) -> i32;
+    }
+
+    let mut count = 0u32;
+    let mut size = 0u32;
+    // SAFETY: первый вызов с пустым буфером только возвращает нужный размер
+    if unsafe { GetUserPreferredUILanguages(MUI_LANGUAGE_NAME, &mut count, std::ptr::null_mut(), &mut size) } == 0 {
+        return Vec::new();
+    }
+    let mut buffer = vec![0u16; size as usize];
+    // SAFETY: буфер передаётся вместе со своим размером, функция пишет не больше него
+    if unsafe { GetUserPreferredUILanguages(MUI_LANGUAGE_NAME, &mut count, buffer.as_mut_ptr(), &mut size) } == 0 {
+        return Vec::new();
+    }
+    parse_multi_string(&buffer)
+}
+
+/// Теги языков в порядке предпочтения пользователя (пустой список, если ОС их не сообщила)
+#[cfg(target_os = "macos")]
+pub fn preferred_languages() -> Vec<String> {
+    std::process::Command::new("/usr/bin/defaults")
+        .args(["read", "-g", "AppleLanguages"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_apple_languages(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// На Linux язык задают переменные окружения, их разбирает `locale_from_env`
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn preferred_languages() -> Vec<String> {
+    Vec::new()
+}
+
+/// Строки, разделённые `\0`, с `\0\0` в конце (формат `MULTI_SZ`)
+#[cfg(any(windows, test))]
+pub fn parse_multi_string(buffer: &[u16]) -> Vec<String> {
+    buffer.split(|&c| c == 0)
+        .filter(|name| !name.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect()
+}
+
+/// Массив из `defaults read -g AppleLanguages`: `(\n    "en-US",\n    ru\n)`
+#[cfg(any(target_os = "macos", test))]
+pub fn parse_apple_languages(output: &str) -> Vec<String> {
+    output.lines()
+        .map(|line| line.trim().trim_end_matches(',').trim_matches('"'))
+        .filter(|tag| !tag.is_empty() && *tag != "(" && *tag != ")")
+        .map(str::to_string)
+        .collect()
+}
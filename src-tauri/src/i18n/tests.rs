@@ -0,0 +1,66 @@
+use super::*;
+
+#[test]
+fn test_locale_parse_accepts_bcp47_and_posix_tags() {
+    assert_eq!(Locale::parse("ru"), Some(Locale::Ru));
+    assert_eq!(Locale::parse("ru_RU.UTF-8"), Some(Locale::Ru));
+    assert_eq!(Locale::parse("pt-BR"), Some(Locale::PtBr));
+    assert_eq!(Locale::parse("pt_PT@euro"), Some(Locale::PtBr));
+    assert_eq!(Locale::parse("EN_us"), Some(Locale::En));
+    assert_eq!(Locale::parse("de_DE.UTF-8"), None);
+    assert_eq!(Locale::parse("C"), None);
+}
+
+#[test]
+fn test_locale_from_env_uses_first_supported_value() {
+    let env = |values: &'static [(&'static str, &'static str)]| {
+        move |name: &str| values.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string())
+    };
+    assert_eq!(locale_from_env(env(&[("LANG", "ru_RU.UTF-8")])), Locale::Ru);
+    assert_eq!(locale_from_env(env(&[("LC_ALL", ""), ("LANG", "pt_BR.UTF-8")])), Locale::PtBr);
+    assert_eq!(locale_from_env(env(&[("LANGUAGE", "de:pt_BR:en"), ("LANG", "ru_RU")])), Locale::PtBr);
+    assert_eq!(locale_from_env(env(&[("LC_MESSAGES", "C"), ("LANG", "POSIX")])), Locale::En);
+    assert_eq!(locale_from_env(env(&[])), Locale::En);
+}
+
+#[test]
+fn test_translate_substitutes_arguments_and_falls_back() {
+    assert_eq!(translate(Locale::Ru, "tab.new", &[]), "Новая вкладка");
+    assert_eq!(translate(Locale::PtBr, "tab.loading", &[]), "Carregando...");
+    assert_eq!(translate(Locale::En, "page.error.serverError", &[("status", "503")]), "The server responded with error 503");
+    assert_eq!(translate(Locale::Ru, "missing.key", &[]), "missing.key");
+}
+
+#[test]
+fn test_catalogs_have_the_same_keys() {
+    let english = &CATALOGS[&Locale::En];
+    assert!(!english.is_empty());
+    for locale in Locale::ALL {
+        let catalog = &CATALOGS[&locale];
+        let mut missing: Vec<&String> = english.keys().filter(|key| !catalog.contains_key(*key)).collect();
+        missing.sort();
+        assert!(missing.is_empty(), "{} catalog is missing {:?}", locale.code(), missing);
+        assert_eq!(catalog.len(), english.len(), "{} catalog has extra keys", locale.code());
+    }
+}
+
+#[test]
+fn test_language_setting() {
+    assert!(validate_language(LANGUAGE_SYSTEM).is_ok());
+    assert!(validate_language("pt-BR").is_ok());
+    assert!(validate_language("klingon").is_err());
+    assert_eq!(locale_from_settings("ru"), Locale::Ru);
+}
+
+#[test]
+fn test_os_languages_take_priority_over_environment() {
+    let lang = |name: &str| (name == "LANG").then(|| "ru_RU.UTF-8".to_string());
+    let preferred = system::parse_apple_languages("(\n    \"de-DE\",\n    \"pt-BR\",\n    en\n)\n");
+    assert_eq!(preferred, vec!["de-DE", "pt-BR", "en"]);
+    assert_eq!(locale_from_system(&preferred, lang), Locale::PtBr);
+    assert_eq!(locale_from_system(&["de-DE".to_string()], lang), Locale::Ru);
+    assert_eq!(locale_from_system(&[], lang), Locale::Ru);
+
+    let windows: Vec<u16> = "ru-RU\0en-US\0\0".encode_utf16().collect();
+    assert_eq!(system::parse_multi_string(&windows), vec!["ru-RU", "en-US"]);
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{LogicalPosition, LogicalSize, Manager, PhysicalSize, Position, Size, Webview, Window};
+use crate::error::LimniError;
+
+/// Отступы интерфейса браузера (панели вкладок, инструментов, закладок, боковая панель)
+/// от краёв окна, в логических пикселях. Webview вкладки занимает всё остальное
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChromeInsets {
+    pub top: f64,
+    pub left: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl Default for ChromeInsets {
+    /// До первого отчёта frontend'а: TabBar (40px) + Toolbar (40px)
+    fn default() -> Self {
+        Self { top: 80.0, left: 0.0, right: 0.0, bottom: 0.0 }
+    }
+}
+
+impl ChromeInsets {
+    /// Проверяет отступы, присланные frontend'ом
+    pub fn validate(&self) -> Result<(), LimniError> {
+        let values = [self.top, self.left, self.right, self.bottom];
+        if values.iter().all(|v| v.is_finite() && *v >= 0.0) {
+            Ok(())
+        } else {
+            Err(LimniError::invalid_argument(format!("Invalid chrome insets: {:?}", self)))
+        }
+    }
+}
+
+/// Отступы интерфейса по окнам
+#[derive(Default)]
+pub struct LayoutState {
+    insets: Mutex<HashMap<String, ChromeInsets>>,
+}
+
+impl LayoutState {
+    pub fn insets(&self, window_label: &str) -> ChromeInsets {
+        self.insets.lock().unwrap().get(window_label).copied().unwrap_or_default()
+    }
+
+    /// Запоминает отступы окна. Возвращает `false`, если они не изменились
+    pub fn set_insets(&self, window_label: &str, insets: ChromeInsets) -> bool {
+        self.insets.lock().unwrap().insert(window_label.to_string(), insets) != Some(insets)
+    }
+
+    pub fn remove(&self, window_label: &str) {
+        self.insets.lock().unwrap().remove(window_label);
+    }
+}
+
+/// Область окна под webview вкладки, в логических пикселях
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContentBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ContentBounds {
+    pub fn position(&self) -> Position {
+        Position::Logical(LogicalPosition::new(self.x, self.y))
+    }
+
+    pub fn size(&self) -> Size {
+        Size::Logical(LogicalSize::new(self.width, self.height))
+    }
+}
+
+/// Коэффициент масштабирования с защитой от некорректных значений
+fn sanitize_scale_factor(scale_factor: f64) -> f64 {
+    if scale_factor.is_finite() && scale_factor > 0.0 {
+        scale_factor
+    } else {
+        1.0
+    }
+}
+
+/// Считает область вкладки по размеру окна в физических пикселях.
+/// `inner_size()` окна отдаёт физические пиксели, а позиция и размер webview
+/// задаются в логических, поэтому размер сначала делится на коэффициент масштабирования
+pub fn content_bounds(inner_size: PhysicalSize<u32>, scale_factor: f64, insets: ChromeInsets) -> ContentBounds {
+    let logical: LogicalSize<f64> = inner_size.to_logical(sanitize_scale_factor(scale_factor));
+    let left = insets.left.clamp(0.0, logical.width);
+    let top = insets.top.clamp(0.0, logical.height);
+    ContentBounds {
+        x: left,
+        y: top,
+        width: (logical.width - left - insets.right.max(0.0)).max(0.0),
+        height: (logical.height - top - insets.bottom.max(0.0)).max(0.0),
+    }
+}
+
+/// Текущие отступы интерфейса окна
+pub fn window_insets(window: &Window) -> ChromeInsets {
+    window.try_state::<LayoutState>()
+        .map(|state| state.insets(window.label()))
+        .unwrap_or_default()
+}
+
+/// Область вкладки для текущего размера, масштаба и отступов окна
+pub fn window_content_bounds(window: &Window) -> Result<ContentBounds, LimniError> {
+    let inner_size = window.inner_size()
+        .map_err(|e| LimniError::internal(format!("Failed to get window size: {}", e)))?;
+    let scale_factor = window.scale_factor()
+        .map_err(|e| LimniError::internal(format!("Failed to get window scale factor: {}", e)))?;
+    Ok(content_bounds(inner_size, scale_factor, window_insets(window)))
+}
+
+/// Ставит webview вкладки в заданную область
+pub fn apply_bounds(webview: &Webview, bounds: ContentBounds) {
+    let _ = webview.set_position(bounds.position());
+    let _ = webview.set_size(bounds.size());
+}
+
+#[cfg(test)]
+mod tests;
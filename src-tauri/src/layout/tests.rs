@@ -0,0 +1,62 @@
+use super::*;
+
+fn header(top: f64) -> ChromeInsets {
+    ChromeInsets { top, left: 0.0, right: 0.0, bottom: 0.0 }
+}
+
+#[test]
+fn test_content_bounds_without_scaling() {
+    let bounds = content_bounds(PhysicalSize::new(1280, 800), 1.0, header(80.0));
+    assert_eq!(bounds, ContentBounds { x: 0.0, y: 80.0, width: 1280.0, height: 720.0 });
+}
+
+#[test]
+fn test_content_bounds_on_hidpi_display() {
+    let bounds = content_bounds(PhysicalSize::new(2560, 1600), 2.0, header(80.0));
+    assert_eq!(bounds, ContentBounds { x: 0.0, y: 80.0, width: 1280.0, height: 720.0 });
+
+    let bounds = content_bounds(PhysicalSize::new(1920, 1200), 1.5, header(80.0));
+    assert_eq!(bounds.width, 1280.0);
+    assert_eq!(bounds.height, 720.0);
+}
+
+#[test]
+fn test_content_bounds_never_negative() {
+    let bounds = content_bounds(PhysicalSize::new(400, 100), 2.0, header(80.0));
+    assert_eq!(bounds.y, 50.0);
+    assert_eq!(bounds.height, 0.0);
+}
+
+#[test]
+fn test_invalid_scale_factor_falls_back_to_one() {
+    assert_eq!(content_bounds(PhysicalSize::new(800, 600), 0.0, header(80.0)).width, 800.0);
+    assert_eq!(content_bounds(PhysicalSize::new(800, 600), f64::NAN, header(80.0)).width, 800.0);
+}
+
+#[test]
+fn test_content_bounds_with_sidebar_and_bookmarks_bar() {
+    let insets = ChromeInsets { top: 108.0, left: 240.0, right: 0.0, bottom: 24.0 };
+    let bounds = content_bounds(PhysicalSize::new(2560, 1600), 2.0, insets);
+    assert_eq!(bounds, ContentBounds { x: 240.0, y: 108.0, width: 1040.0, height: 668.0 });
+}
+
+#[test]
+fn test_insets_are_stored_per_window() {
+    let state = LayoutState::default();
+    assert_eq!(state.insets("main"), ChromeInsets::default());
+
+    assert!(state.set_insets("main", header(108.0)));
+    assert!(!state.set_insets("main", header(108.0)));
+    assert_eq!(state.insets("main").top, 108.0);
+    assert_eq!(state.insets("window-2"), ChromeInsets::default());
+
+    state.remove("main");
+    assert_eq!(state.insets("main"), ChromeInsets::default());
+}
+
+#[test]
+fn test_validate_rejects_negative_and_non_finite_insets() {
+    assert!(header(80.0).validate().is_ok());
+    assert!(header(-1.0).validate().is_err());
+    assert!(header(f64::INFINITY).validate().is_err());
+}
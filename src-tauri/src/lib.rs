@@ -1,75 +1,166 @@
-use tauri::{Manager, LogicalPosition, LogicalSize, Position, Size};
+use tauri::Manager;
 
 // Модули
 mod state;
 mod webview;
 mod commands;
 mod utils;
+mod storage;
+mod history;
+mod bookmarks;
+mod session;
+mod settings;
+mod downloads;
+mod private;
+mod error;
+mod registry;
+mod profile;
+mod containers;
+mod data_store;
+mod layout;
+mod eval;
+mod page_load;
+mod net;
+mod favicon;
+mod public_suffix;
+mod site_names;
+mod i18n;
 
 // Импорты из модулей
-use state::{WebviewState, HEADER_HEIGHT};
+use state::MAIN_WINDOW_LABEL;
+use registry::TabRegistry;
+use history::HistoryState;
+use bookmarks::BookmarksState;
+use session::SessionState;
+use settings::SettingsState;
+use downloads::DownloadsState;
+use profile::ProfileState;
+use containers::ContainersState;
+use layout::LayoutState;
+use eval::EvalState;
+use net::NetworkService;
+use favicon::cache::FaviconCacheState;
+use site_names::SiteNamesState;
 use commands::*;
 
+/// Сохраняет последний снимок сессии и историю, удаляет данные приватных окон.
+/// Вызывается при выходе и перед перезапуском в другом профиле
+pub(crate) fn flush_before_exit(app: &tauri::AppHandle) {
+    session::save_now(app);
+    history::save_now(app);
+    private::wipe_stale_private_data(app);
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .manage(WebviewState::default())
+        .manage(TabRegistry::spawn())
+        .manage(LayoutState::default())
+        .manage(EvalState::default())
         .invoke_handler(tauri::generate_handler![
             create_tab_webview,
             show_tab_webview,
             hide_all_webviews,
             close_tab_webview,
             navigate_webview,
+            retry_navigation,
             reload_tab,
             navigate_back,
             navigate_forward,
             navigate_to_home,
             get_webview_info,
+            get_tabs,
+            set_chrome_insets,
             fetch_favicon_backend,
+            get_cached_favicon,
+            clear_favicon_cache,
             fetch_page_title_backend,
+            get_page_metadata,
             get_webview_url,
             update_webview_title,
+            eval_in_tab_callback,
+            report_tab_audio,
             open_url_in_new_tab,
             mute_webview,
-            unmute_webview
+            unmute_webview,
+            get_history,
+            search_history,
+            delete_history_entry,
+            clear_history,
+            get_bookmarks,
+            search_bookmarks,
+            add_bookmark,
+            create_bookmark_folder,
+            rename_bookmark,
+            set_bookmark_tags,
+            move_bookmark,
+            delete_bookmark,
+            restore_session,
+            set_tab_pinned,
+            reorder_tabs,
+            get_settings,
+            update_settings,
+            get_downloads,
+            cancel_download,
+            retry_download,
+            open_download_folder,
+            clear_downloads,
+            create_new_window,
+            close_current_window,
+            create_private_window,
+            is_private_window,
+            get_profiles,
+            create_profile,
+            delete_profile,
+            switch_profile,
+            get_containers,
+            create_container,
+            update_container,
+            delete_container,
+            assign_site_container,
+            get_tab_container,
+            get_locale,
+            get_site_names,
+            set_site_name
         ])
         .setup(|app| {
-            // Добавляем обработчик изменения размера окна для правильного позиционирования webview'ов
-            let app_handle = app.app_handle();
-            if let Some(main_window) = app.get_window("main") {
-                let app_handle_clone = app_handle.clone();
-                let _ = main_window.on_window_event(move |event| {
-                    if let tauri::WindowEvent::Resized(_) = event {
-                        // Клонируем app_handle_clone перед использованием в async move
-                        let app_handle_for_async = app_handle_clone.clone();
-                        // При изменении размера окна, обновляем позиции всех видимых webview'ов
-                        tauri::async_runtime::spawn(async move {
-                            let state = app_handle_for_async.state::<WebviewState>();
-                            let webviews = state.webviews.lock().unwrap();
-                            
-                            if let Some(main_window) = app_handle_for_async.get_window("main") {
-                                if let Ok(window_size) = main_window.inner_size() {
-                                    let position = Position::Logical(LogicalPosition::new(0.0, HEADER_HEIGHT));
-                                    let size = Size::Logical(LogicalSize::new(
-                                        window_size.width as f64, 
-                                        window_size.height as f64 - HEADER_HEIGHT
-                                    ));
-                                    
-                                    for webview_label in webviews.values() {
-                                        if let Some(webview) = app_handle_for_async.get_webview(webview_label) {
-                                            let _ = webview.set_position(position);
-                                            let _ = webview.set_size(size);
-                                        }
-                                    }
-                                }
-                            }
-                        });
-                    }
-                });
+            // Выбираем профиль до загрузки данных: от него зависят пути всех файлов
+            app.manage(ProfileState::load(app.handle()));
+            // Удаляем данные приватных окон, оставшиеся после аварийного завершения
+            private::wipe_stale_private_data(app.handle());
+            // Загружаем настройки браузера с диска
+            app.manage(SettingsState::load(app.handle()));
+            // Общий HTTP клиент бэкенда с прокси из настроек
+            app.manage(NetworkService::new(&settings::current(app.handle()).proxy));
+            // Загружаем историю посещений с диска
+            app.manage(HistoryState::load(app.handle()));
+            history::start_autosave(app.handle());
+            // Загружаем кэш значков сайтов
+            app.manage(FaviconCacheState::load(app.handle()));
+            // Загружаем пользовательские названия сайтов
+            app.manage(SiteNamesState::load(app.handle()));
+            // Загружаем закладки с диска
+            app.manage(BookmarksState::load(app.handle()));
+            // Загружаем контейнеры и закреплённые за ними сайты
+            app.manage(ContainersState::load(app.handle()));
+            // Загружаем сессию прошлого запуска и включаем её периодическое сохранение
+            app.manage(SessionState::load(app.handle()));
+            session::start_autosave(app.handle());
+            // Загружаем список загрузок
+            app.manage(DownloadsState::load(app.handle()));
+            
+            // Добавляем обработчики событий окна для правильного позиционирования webview'ов
+            if let Some(main_window) = app.get_window(MAIN_WINDOW_LABEL) {
+                webview::setup_window_events(app.handle(), &main_window);
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                flush_before_exit(app_handle);
+            }
+        });
 }
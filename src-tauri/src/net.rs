@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use url::Url;
+use crate::error::LimniError;
+
+/// User-Agent фоновых запросов браузера
+pub const USER_AGENT: &str = concat!("Limni/", env!("CARGO_PKG_VERSION"));
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Сколько можно ждать следующих данных ответа (действует и для загрузок файлов)
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Общее время запроса через `send`, если запрос не задал своё
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const MAX_REDIRECTS: usize = 10;
+
+/// Сколько запросов одновременно может идти к одному хосту
+pub const MAX_REQUESTS_PER_HOST: usize = 4;
+
+/// Проверяет адрес прокси из настроек (пустая строка — без прокси)
+pub fn parse_proxy(proxy: &str) -> Result<Option<reqwest::Proxy>, LimniError> {
+    let proxy = proxy.trim();
+    if proxy.is_empty() {
+        return Ok(None);
+    }
+    let parsed = Url::parse(proxy).map_err(|e| LimniError::invalid_url(proxy, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(LimniError::unsupported_scheme(proxy));
+    }
+    reqwest::Proxy::all(parsed.as_str())
+        .map(Some)
+        .map_err(|e| LimniError::invalid_url(proxy, e))
+}
+
+fn build_client(proxy: Option<reqwest::Proxy>) -> Result<Client, LimniError> {
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS));
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
+    }
+    builder.build().map_err(|e| LimniError::internal(format!("Failed to build HTTP client: {}", e)))
+}
+
+/// Ключ ограничения параллельных запросов: хост и порт
+fn host_key(url: &Url) -> String {
+    format!("{}:{}", url.host_str().unwrap_or(""), url.port_or_known_default().unwrap_or(0))
+}
+
+/// Сетевой сервис бэкенда: один пул соединений на всё приложение.
+/// Клиент не хранит cookie и не кэширует ответы на диск, поэтому запросы
+/// из приватных окон не оставляют следов
+pub struct NetworkService {
+    client: RwLock<Client>,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl NetworkService {
+    /// Создаёт сервис с прокси из настроек. Некорректный прокси не мешает запуску
+    pub fn new(proxy: &str) -> Self {
+        let proxy = parse_proxy(proxy).unwrap_or_else(|e| {
+            println!("🦀 Rust: Ignoring invalid proxy setting: {}", e);
+            None
+        });
+        let client = build_client(proxy)
+            .or_else(|_| build_client(None))
+            .unwrap_or_default();
+        Self {
+            client: RwLock::new(client),
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Пересоздаёт клиент с новым прокси. Запросы, которые уже идут, завершаются на старом
+    pub fn set_proxy(&self, proxy: &str) -> Result<(), LimniError> {
+        let client = build_client(parse_proxy(proxy)?)?;
+        *self.client.write().unwrap() = client;
+        Ok(())
+    }
+
+    /// Общий клиент для долгих запросов, которые не нужно ограничивать
+    /// по хосту и общему времени (загрузки файлов)
+    pub fn client(&self) -> Client {
+        self.client.read().unwrap().clone()
+    }
+
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.client().get(url)
+    }
+
+    fn host_semaphore(&self, url: &Url) -> Arc<Semaphore> {
+        self.hosts.lock().unwrap()
+            .entry(host_key(url))
+            .or_insert_with(|| Arc::new(Semaphore::new(MAX_REQUESTS_PER_HOST)))
+            .clone()
+    }
+
+    /// Отправляет запрос с учётом ограничения по хосту. Место в очереди хоста
+    /// занято, пока жив ответ, то есть и во время чтения тела. Ошибка reqwest
+    /// возвращается как есть, чтобы её можно было классифицировать
+    pub async fn send(&self, request: RequestBuilder) -> Result<HostResponse, reqwest::Error> {
+        let mut request = request.build()?;
+        if request.timeout().is_none() {
+            *request.timeout_mut() = Some(REQUEST_TIMEOUT);
+        }
+        let semaphore = self.host_semaphore(request.url());
+        let permit = semaphore.acquire_owned().await.expect("host semaphore is never closed");
+        let response = self.client().execute(request).await?;
+        Ok(HostResponse { response, _permit: permit })
+    }
+
+    /// Выполняет запрос и читает тело не больше `max_bytes`
+    pub async fn fetch(&self, request: RequestBuilder, max_bytes: usize) -> Result<FetchedResponse, LimniError> {
+        let mut response = self.send(request).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.read_limited(max_bytes).await?;
+        Ok(FetchedResponse { status, headers, body })
+    }
+}
+
+/// Ответ, который держит место в очереди своего хоста
+pub struct HostResponse {
+    response: Response,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl HostResponse {
+    pub fn status(&self) -> StatusCode {
+        self.response.status()
+    }
+
+    /// Адрес ответа после редиректов
+    pub fn url(&self) -> &Url {
+        self.response.url()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    /// Следующий кусок тела (`None` — тело закончилось)
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, LimniError> {
+        Ok(self.response.chunk().await?.map(|chunk| chunk.to_vec()))
+    }
+
+    /// Читает тело целиком, но не больше `max_bytes`
+    pub async fn read_limited(&mut self, max_bytes: usize) -> Result<Vec<u8>, LimniError> {
+        let too_large = || LimniError::network(format!("Response is larger than {} bytes", max_bytes));
+        if self.response.content_length().is_some_and(|len| len > max_bytes as u64) {
+            return Err(too_large());
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            if body.len() + chunk.len() > max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Читает начало тела, пока `done` не вернёт `true` или не наберётся
+    /// `max_bytes`. В отличие от `read_limited`, длинный ответ не ошибка:
+    /// лишнее просто не скачивается
+    pub async fn read_until(&mut self, max_bytes: usize, done: impl Fn(&[u8]) -> bool) -> Result<Vec<u8>, LimniError> {
+        let mut body = Vec::new();
+        while body.len() < max_bytes {
+            let Some(chunk) = self.chunk().await? else {
+                break;
+            };
+            let take = chunk.len().min(max_bytes - body.len());
+            body.extend_from_slice(&chunk[..take]);
+            if done(&body) {
+                break;
+            }
+        }
+        Ok(body)
+    }
+}
+
+/// Полностью прочитанный ответ
+#[derive(Debug)]
+pub struct FetchedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl FetchedResponse {
+    /// Значение заголовка, если это корректная строка
+    pub fn header(&self, name: reqwest::header::HeaderName) -> Option<String> {
+        self.headers.get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    }
+}
+
+/// Сетевой сервис приложения
+pub fn network(app: &AppHandle) -> tauri::State<'_, NetworkService> {
+    app.state::<NetworkService>()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn test_parse_proxy_accepts_http_proxies_and_empty_setting() {
+    assert!(parse_proxy("").unwrap().is_none());
+    assert!(parse_proxy("   ").unwrap().is_none());
+    assert!(parse_proxy("http://127.0.0.1:8080").unwrap().is_some());
+    assert!(parse_proxy("https://proxy.example.com").unwrap().is_some());
+}
+
+#[test]
+fn test_parse_proxy_rejects_invalid_values() {
+    assert_eq!(parse_proxy("not a url").unwrap_err().code(), "INVALID_URL");
+    assert_eq!(parse_proxy("ftp://proxy.example.com").unwrap_err().code(), "UNSUPPORTED_SCHEME");
+}
+
+#[test]
+fn test_host_key_includes_default_port() {
+    let key = |url: &str| host_key(&Url::parse(url).unwrap());
+    assert_eq!(key("https://example.com/a"), "example.com:443");
+    assert_eq!(key("http://example.com/b"), "example.com:80");
+    assert_eq!(key("http://example.com:8080/"), "example.com:8080");
+}
+
+#[test]
+fn test_requests_to_one_host_share_a_limit() {
+    let service = NetworkService::new("");
+    let url = Url::parse("https://example.com/").unwrap();
+    let first = service.host_semaphore(&url);
+    let second = service.host_semaphore(&Url::parse("https://example.com/other").unwrap());
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(first.available_permits(), MAX_REQUESTS_PER_HOST);
+}
@@ -0,0 +1,310 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use crate::error::LimniError;
+use crate::eval::{eval_in_tab, DEFAULT_EVAL_TIMEOUT};
+use crate::i18n::t_args;
+use crate::net::network;
+use crate::registry::{registry, LoadState};
+use crate::utils::now_millis;
+
+/// Тайминги загрузки документа из Navigation Timing API,
+/// в миллисекундах от начала навигации
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NavigationTiming {
+    /// HTTP статус ответа (есть не во всех движках)
+    pub status: Option<u16>,
+    pub redirect_count: u32,
+    pub time_to_first_byte: Option<f64>,
+    pub dom_content_loaded: Option<f64>,
+    pub load: Option<f64>,
+    pub transfer_size: Option<u64>,
+}
+
+/// Выражение, которое возвращает `NavigationTiming` текущего документа или `null`
+const NAVIGATION_TIMING_EXPRESSION: &str = r#"(function () {
+    var entry = performance.getEntriesByType('navigation')[0];
+    if (!entry) return null;
+    function mark(value) { return value > 0 ? value : null; }
+    return {
+        status: entry.responseStatus || null,
+        redirectCount: entry.redirectCount || 0,
+        timeToFirstByte: mark(entry.responseStart),
+        domContentLoaded: mark(entry.domContentLoadedEventEnd),
+        load: mark(entry.loadEventEnd),
+        transferSize: typeof entry.transferSize === 'number' ? entry.transferSize : null
+    };
+})()"#;
+
+/// Служебные адреса, по которым webview показывает свои страницы ошибок
+const ERROR_DOCUMENT_PREFIXES: &[&str] = &["chrome-error://"];
+
+/// Сколько ждём ответа сервера при проверке адреса
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Класс ошибки загрузки страницы, по нему frontend выбирает текст страницы ошибки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NavigationErrorKind {
+    /// Имя сайта не удалось разрешить
+    Dns,
+    ConnectionRefused,
+    Timeout,
+    /// Ошибка TLS: недоверенный или просроченный сертификат, сбой рукопожатия
+    Tls,
+    /// Сервер ответил ошибкой 5xx
+    ServerError,
+    /// Прочие сетевые ошибки (сброс соединения, недоступная сеть)
+    ConnectionFailed,
+}
+
+impl NavigationErrorKind {
+    /// Имя класса в ключах каталога сообщений (совпадает с `errorKind` во frontend)
+    fn key(self) -> &'static str {
+        match self {
+            NavigationErrorKind::Dns => "dns",
+            NavigationErrorKind::ConnectionRefused => "connectionRefused",
+            NavigationErrorKind::Timeout => "timeout",
+            NavigationErrorKind::Tls => "tls",
+            NavigationErrorKind::ServerError => "serverError",
+            NavigationErrorKind::ConnectionFailed => "connectionFailed",
+        }
+    }
+}
+
+/// Классифицированная ошибка загрузки
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationError {
+    pub kind: NavigationErrorKind,
+    pub reason: String,
+}
+
+impl NavigationError {
+    fn new(kind: NavigationErrorKind, reason: impl Into<String>) -> Self {
+        Self { kind, reason: reason.into() }
+    }
+
+    /// Заголовок страницы ошибки на языке интерфейса
+    pub fn message(&self, app: &AppHandle) -> String {
+        let status = self.status().map(|status| status.to_string()).unwrap_or_default();
+        t_args(app, &format!("page.error.{}", self.kind.key()), &[("status", &status)])
+    }
+
+    /// Пояснение к ошибке на языке интерфейса
+    pub fn description(&self, app: &AppHandle) -> String {
+        t_args(app, &format!("page.errorDescription.{}", self.kind.key()), &[])
+    }
+
+    /// HTTP статус ошибки сервера
+    fn status(&self) -> Option<u16> {
+        self.reason.strip_prefix("HTTP ").and_then(|code| code.parse::<u16>().ok())
+    }
+}
+
+/// Итог загрузки страницы
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadOutcome {
+    Finished,
+    /// Сервер ответил ошибкой, класс известен сразу
+    Failed(NavigationError),
+    /// Документ без ответа сервера: причину выясняет проверка адреса
+    NoResponse,
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Показывает ли webview собственную страницу ошибки вместо документа сайта
+pub fn is_error_document(url: &str) -> bool {
+    ERROR_DOCUMENT_PREFIXES.iter().any(|prefix| url.starts_with(prefix))
+}
+
+/// Решает, загрузилась ли страница. Ответ сервера с кодом 5xx — ошибка,
+/// страницы 4xx — обычный контент сайта. Документ без ответа сервера требует
+/// проверки адреса. Если тайминги прочитать не удалось (скрипт не ответил),
+/// страница считается загруженной: повторный запрос ради неё не нужен
+pub fn classify(url: &str, timing: Option<&NavigationTiming>) -> LoadOutcome {
+    if !is_http_url(url) {
+        return LoadOutcome::Finished;
+    }
+    let Some(timing) = timing else {
+        return LoadOutcome::Finished;
+    };
+
+    match timing.status {
+        Some(status) if status >= 500 => LoadOutcome::Failed(server_error(status)),
+        _ if timing.time_to_first_byte.is_none() => LoadOutcome::NoResponse,
+        _ => LoadOutcome::Finished,
+    }
+}
+
+fn server_error(status: u16) -> NavigationError {
+    NavigationError::new(NavigationErrorKind::ServerError, format!("HTTP {}", status))
+}
+
+/// Классифицирует сетевую ошибку по тексту всей цепочки причин
+/// (reqwest -> hyper -> io/dns/tls), в которой и видна настоящая причина
+pub fn classify_network_error(message: &str, is_timeout: bool) -> NavigationError {
+    let lower = message.to_lowercase();
+    let kind = if is_timeout || lower.contains("timed out") {
+        NavigationErrorKind::Timeout
+    } else if ["dns error", "failed to lookup address", "name or service not known", "no such host", "nodename nor servname"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+    {
+        NavigationErrorKind::Dns
+    } else if lower.contains("connection refused") || lower.contains("actively refused") {
+        NavigationErrorKind::ConnectionRefused
+    } else if ["certificate", "tls", "ssl", "handshake"].iter().any(|needle| lower.contains(needle)) {
+        NavigationErrorKind::Tls
+    } else {
+        NavigationErrorKind::ConnectionFailed
+    };
+    NavigationError::new(kind, message)
+}
+
+/// Текст ошибки вместе со всеми её причинами
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Проверяет адрес прямым запросом. `None` — сервер отвечает нормально
+pub async fn probe(app: &AppHandle, url: &str) -> Option<NavigationError> {
+    let network = network(app);
+    let request = network.get(url).timeout(PROBE_TIMEOUT);
+    match network.send(request).await {
+        Ok(response) if response.status().is_server_error() => Some(server_error(response.status().as_u16())),
+        Ok(_) => None,
+        Err(e) => Some(classify_network_error(&error_chain(&e), e.is_timeout())),
+    }
+}
+
+/// Страница начала загружаться: отмечаем время начала и сообщаем во frontend
+pub async fn load_started(app: &AppHandle, tab_id: &str, url: &str) {
+    let started_at = now_millis();
+    let navigation_id = registry(app).modify(tab_id, move |tab| {
+        tab.load_state = LoadState::Loading;
+        tab.load_started_at = Some(started_at);
+        tab.navigation_id
+    }).await;
+    let Some(navigation_id) = navigation_id else {
+        return;
+    };
+
+    let _ = app.emit("page-load-started", serde_json::json!({
+        "tabId": tab_id,
+        "navigationId": navigation_id,
+        "url": url,
+        "startedAt": started_at
+    }));
+}
+
+/// Страница загрузилась: читаем тайминги документа и сообщаем во frontend,
+/// успешно ли прошла загрузка
+pub async fn load_finished(app: &AppHandle, tab_id: &str, url: &str) {
+    let Some(tab) = registry(app).get(tab_id).await else {
+        return;
+    };
+    let elapsed_ms = tab.load_started_at.map(|started| now_millis().saturating_sub(started));
+    // Страница ошибки webview имеет служебный адрес (chrome-error://...): документ сайта
+    // не загрузился, поэтому проверяем адрес, на который вкладка пыталась перейти
+    let error_document = is_error_document(url) && is_http_url(&tab.url);
+    let url = if error_document { tab.url.as_str() } else { url };
+
+    let timing = if error_document {
+        None
+    } else {
+        match read_navigation_timing(app, tab_id).await {
+            Ok(timing) => timing,
+            Err(e) => {
+                println!("🦀 Rust: Failed to read navigation timing of tab {}: {}", tab_id, e);
+                None
+            }
+        }
+    };
+
+    let outcome = if error_document { LoadOutcome::NoResponse } else { classify(url, timing.as_ref()) };
+    let error = match outcome {
+        LoadOutcome::Finished => None,
+        LoadOutcome::Failed(error) => Some(error),
+        LoadOutcome::NoResponse => probe(app, url).await,
+    };
+
+    match error {
+        None => {
+            let _ = app.emit("page-load-finished", serde_json::json!({
+                "tabId": tab_id,
+                "navigationId": tab.navigation_id,
+                "url": url,
+                "status": timing.as_ref().and_then(|t| t.status),
+                "timing": timing,
+                "elapsedMs": elapsed_ms
+            }));
+        }
+        Some(error) => {
+            println!("🦀 Rust: Page load failed in tab {}: {}", tab_id, error.reason);
+            let navigation_id = tab.navigation_id;
+            registry(app).update(tab_id, move |tab| {
+                if tab.navigation_id == navigation_id {
+                    tab.load_state = LoadState::Failed;
+                }
+            });
+            emit_load_failed(app, tab_id, navigation_id, url, &error, elapsed_ms);
+        }
+    }
+}
+
+/// Сообщает во frontend о неудачной загрузке
+fn emit_load_failed(app: &AppHandle, tab_id: &str, navigation_id: u64, url: &str, error: &NavigationError, elapsed_ms: Option<u64>) {
+    let _ = app.emit("page-load-failed", serde_json::json!({
+        "tabId": tab_id,
+        "navigationId": navigation_id,
+        "url": url,
+        "status": error.status(),
+        "errorKind": error.kind,
+        "reason": error.reason,
+        "message": error.message(app),
+        "description": error.description(app),
+        "elapsedMs": elapsed_ms
+    }));
+}
+
+/// Повторяет последнюю навигацию вкладки. Адрес сначала проверяется запросом:
+/// пока сайт недоступен, вкладка остаётся на странице ошибки, а вызывающий
+/// получает ошибку `NETWORK`
+pub async fn retry_navigation(app: &AppHandle, tab_id: &str) -> Result<(), LimniError> {
+    let tab = registry(app).get(tab_id).await
+        .ok_or_else(|| LimniError::tab_not_found(tab_id))?;
+
+    if is_http_url(&tab.url) {
+        if let Some(error) = probe(app, &tab.url).await {
+            emit_load_failed(app, tab_id, tab.navigation_id, &tab.url, &error, None);
+            return Err(LimniError::network(error.reason));
+        }
+    }
+
+    println!("🦀 Rust: Retrying navigation of tab {} to {}", tab_id, tab.url);
+    crate::webview::navigate_webview_impl(app.clone(), tab_id.to_string(), tab.url).await
+}
+
+async fn read_navigation_timing(app: &AppHandle, tab_id: &str) -> Result<Option<NavigationTiming>, LimniError> {
+    let value = eval_in_tab(app, tab_id, NAVIGATION_TIMING_EXPRESSION, DEFAULT_EVAL_TIMEOUT).await?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    serde_json::from_value(value)
+        .map(Some)
+        .map_err(|e| LimniError::internal(format!("Invalid navigation timing: {}", e)))
+}
+
+#[cfg(test)]
+mod tests;
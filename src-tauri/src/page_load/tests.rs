@@ -0,0 +1,76 @@
+use super::*;
+
+fn timing(status: Option<u16>, time_to_first_byte: Option<f64>) -> NavigationTiming {
+    NavigationTiming { status, time_to_first_byte, ..Default::default() }
+}
+
+#[test]
+fn test_classify_successful_and_client_error_pages_as_finished() {
+    let url = "https://example.com/";
+    assert_eq!(classify(url, Some(&timing(Some(200), Some(120.0)))), LoadOutcome::Finished);
+    assert_eq!(classify(url, Some(&timing(Some(404), Some(80.0)))), LoadOutcome::Finished);
+    // Движок без responseStatus
+    assert_eq!(classify(url, Some(&timing(None, Some(80.0)))), LoadOutcome::Finished);
+}
+
+#[test]
+fn test_classify_server_errors_and_missing_response_as_failed() {
+    let url = "https://example.com/";
+    assert_eq!(
+        classify(url, Some(&timing(Some(503), Some(40.0)))),
+        LoadOutcome::Failed(NavigationError::new(NavigationErrorKind::ServerError, "HTTP 503"))
+    );
+    assert_eq!(classify(url, Some(&timing(None, None))), LoadOutcome::NoResponse);
+}
+
+#[test]
+fn test_classify_error_documents_and_local_pages() {
+    assert_eq!(classify("https://example.com/", None), LoadOutcome::Finished);
+    assert_eq!(classify("about:blank", Some(&timing(None, None))), LoadOutcome::Finished);
+    assert!(is_error_document("chrome-error://chromewebdata/"));
+    assert!(!is_error_document("https://example.com/"));
+}
+
+#[test]
+fn test_navigation_timing_deserializes_partial_entries() {
+    let parsed: NavigationTiming = serde_json::from_value(serde_json::json!({
+        "status": 200,
+        "timeToFirstByte": 35.5,
+        "load": null
+    })).unwrap();
+    assert_eq!(parsed.status, Some(200));
+    assert_eq!(parsed.time_to_first_byte, Some(35.5));
+    assert_eq!(parsed.redirect_count, 0);
+    assert_eq!(parsed.load, None);
+}
+
+#[test]
+fn test_classify_network_errors_by_cause_chain() {
+    let kind = |message: &str| classify_network_error(message, false).kind;
+    assert_eq!(
+        kind("error sending request for url (https://nope.invalid/): client error (Connect): dns error: failed to lookup address information: Name or service not known"),
+        NavigationErrorKind::Dns
+    );
+    assert_eq!(
+        kind("error sending request: client error (Connect): tcp connect error: Connection refused (os error 111)"),
+        NavigationErrorKind::ConnectionRefused
+    );
+    assert_eq!(
+        kind("error sending request: client error (Connect): invalid peer certificate: Expired"),
+        NavigationErrorKind::Tls
+    );
+    assert_eq!(kind("connection reset by peer"), NavigationErrorKind::ConnectionFailed);
+    assert_eq!(classify_network_error("operation", true).kind, NavigationErrorKind::Timeout);
+}
+
+#[test]
+fn test_every_error_kind_has_catalog_texts() {
+    use crate::i18n::{translate, Locale};
+    use NavigationErrorKind::*;
+    for kind in [Dns, ConnectionRefused, Timeout, Tls, ServerError, ConnectionFailed] {
+        for key in [format!("page.error.{}", kind.key()), format!("page.errorDescription.{}", kind.key())] {
+            assert_ne!(translate(Locale::En, &key, &[]), key);
+        }
+        assert_eq!(serde_json::to_value(kind).unwrap(), kind.key());
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use crate::error::LimniError;
+use crate::registry::registry;
+use crate::profile;
+use crate::data_store;
+
+/// Префикс меток приватных окон
+pub const PRIVATE_WINDOW_PREFIX: &str = "private-";
+
+/// Имя временной директории с данными приватных окон
+const PRIVATE_DATA_DIR: &str = "limni-private";
+
+/// Является ли окно приватным
+pub fn is_private_window(window_label: &str) -> bool {
+    window_label.starts_with(PRIVATE_WINDOW_PREFIX)
+}
+
+/// Принадлежит ли вкладка приватному окну.
+/// Для таких вкладок не ведётся история, сессия и список загрузок
+pub async fn is_private_tab(app: &AppHandle, tab_id: &str) -> bool {
+    registry(app).get(tab_id).await
+        .is_some_and(|tab| is_private_window(&tab.window_label))
+}
+
+/// Корень временных данных приватных окон. Разделён по профилям,
+/// чтобы запуск одного профиля не удалял данные другого, уже запущенного
+fn private_data_root(app: &AppHandle) -> Result<PathBuf, LimniError> {
+    app.path().temp_dir()
+        .map(|dir| dir.join(PRIVATE_DATA_DIR).join(profile::current_name(app)))
+        .map_err(|e| LimniError::io(format!("Failed to resolve temp dir: {}", e)))
+}
+
+/// Временная директория данных webview (cookies, localStorage, кэш) приватного окна.
+/// Все вкладки одного приватного окна делят её между собой
+pub fn private_data_dir(app: &AppHandle, window_label: &str) -> Result<PathBuf, LimniError> {
+    let dir = private_data_root(app)?.join(window_label);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| LimniError::io(format!("Failed to create private data dir: {}", e)))?;
+    Ok(dir)
+}
+
+/// Удаляет данные приватного окна после его закрытия
+pub fn wipe_private_data(app: &AppHandle, window_label: &str) {
+    let Ok(dir) = private_data_root(app).map(|root| root.join(window_label)) else {
+        return;
+    };
+    if !dir.exists() {
+        return;
+    }
+
+    // Процессы webview могут ещё держать файлы, поэтому даём им немного времени
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        data_store::remove_storage(&app, &dir);
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => println!("🦀 Rust: Wiped private data: {}", dir.display()),
+            Err(e) => println!("🦀 Rust: Failed to wipe private data {}: {}", dir.display(), e),
+        }
+    });
+}
+
+/// Удаляет данные приватных окон, оставшиеся после аварийного завершения
+pub fn wipe_stale_private_data(app: &AppHandle) {
+    if let Ok(root) = private_data_root(app) {
+        if root.exists() {
+            // Хранилища по директориям окон удаляем до самих директорий
+            for entry in std::fs::read_dir(&root).into_iter().flatten().flatten() {
+                data_store::remove_storage(app, &entry.path());
+            }
+            if let Err(e) = std::fs::remove_dir_all(&root) {
+                println!("🦀 Rust: Failed to wipe stale private data: {}", e);
+            }
+        }
+    }
+}
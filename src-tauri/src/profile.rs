@@ -0,0 +1,306 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use crate::error::LimniError;
+use crate::storage::{app_data_root, read_json, write_json_atomic};
+
+/// Профиль, который используется, если другой не выбран
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Аргумент командной строки для выбора профиля: `--profile work` или `--profile=work`
+const PROFILE_ARG: &str = "--profile";
+
+/// Директория с данными именованных профилей
+const PROFILES_DIR: &str = "profiles";
+
+/// Файл со списком профилей в корне директории данных приложения
+const PROFILES_FILE: &str = "profiles.json";
+
+/// Поддиректория профиля с данными webview (cookies, localStorage, кэш)
+const WEBVIEW_DATA_DIR: &str = "webview";
+
+/// Максимальная длина имени профиля
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// Список профилей на диске
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProfilesIndex {
+    pub profiles: Vec<String>,
+    /// Профиль, который откроется при следующем запуске без `--profile`
+    pub last_used: Option<String>,
+}
+
+/// Профили для frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilesInfo {
+    pub profiles: Vec<String>,
+    pub current: String,
+}
+
+/// Профиль текущего запуска. Выбирается один раз при старте и не меняется до перезапуска
+pub struct ProfileState {
+    pub name: String,
+    /// Директория данных профиля (история, закладки, настройки, сессия, загрузки)
+    pub dir: Option<PathBuf>,
+    root: Option<PathBuf>,
+    index: Mutex<ProfilesIndex>,
+}
+
+impl Default for ProfileState {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            dir: None,
+            root: None,
+            index: Mutex::new(ProfilesIndex::default()),
+        }
+    }
+}
+
+/// Проверяет имя профиля: латиница, цифры, `-` и `_`, чтобы имя было безопасно как имя директории
+pub fn validate_profile_name(name: &str) -> Result<(), LimniError> {
+    if name.is_empty() || name.len() > MAX_PROFILE_NAME_LEN {
+        return Err(LimniError::invalid_argument(format!("Profile name must be 1-{} characters long", MAX_PROFILE_NAME_LEN)));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(LimniError::invalid_argument(format!("Invalid profile name: {}", name)));
+    }
+    Ok(())
+}
+
+/// Ищет имя профиля в аргументах командной строки
+pub fn profile_from_args<I, S>(args: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        if arg == PROFILE_ARG {
+            return args.next().map(|value| value.as_ref().to_string());
+        }
+        if let Some(value) = arg.strip_prefix(PROFILE_ARG).and_then(|rest| rest.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Заменяет профиль в аргументах командной строки (для перезапуска в другом профиле)
+pub fn replace_profile_arg(args: &[OsString], name: &str) -> Vec<OsString> {
+    let mut result = Vec::with_capacity(args.len() + 2);
+    let mut skip_value = false;
+    for arg in args {
+        if skip_value {
+            skip_value = false;
+            continue;
+        }
+        match arg.to_str() {
+            Some(PROFILE_ARG) => skip_value = true,
+            Some(s) if s.starts_with(PROFILE_ARG) && s[PROFILE_ARG.len()..].starts_with('=') => {}
+            _ => result.push(arg.clone()),
+        }
+    }
+    result.push(PROFILE_ARG.into());
+    result.push(name.into());
+    result
+}
+
+/// Директория данных профиля. Профиль по умолчанию живёт в корне директории данных,
+/// чтобы данные, созданные до появления профилей, остались на месте
+fn profile_dir(root: &std::path::Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        root.to_path_buf()
+    } else {
+        root.join(PROFILES_DIR).join(name)
+    }
+}
+
+impl ProfileState {
+    /// Выбирает профиль: `--profile` из командной строки, иначе последний использованный
+    pub fn load(app: &AppHandle) -> Self {
+        let root = match app_data_root(app) {
+            Ok(root) => root,
+            Err(e) => {
+                println!("🦀 Rust: Profiles are unavailable: {}", e);
+                return Self::default();
+            }
+        };
+
+        let index_path = root.join(PROFILES_FILE);
+        let mut index: ProfilesIndex = read_json(&index_path).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load profiles, using defaults: {}", e);
+            ProfilesIndex::default()
+        });
+
+        let requested = profile_from_args(std::env::args().skip(1));
+        let name = requested
+            .filter(|name| match validate_profile_name(name) {
+                Ok(()) => true,
+                Err(e) => {
+                    println!("🦀 Rust: Ignoring --profile: {}", e);
+                    false
+                }
+            })
+            .or_else(|| index.last_used.clone().filter(|name| index.profiles.contains(name)))
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+        // Профиль из командной строки создаётся при первом запуске с ним
+        for profile in [DEFAULT_PROFILE, name.as_str()] {
+            if !index.profiles.iter().any(|p| p == profile) {
+                index.profiles.push(profile.to_string());
+            }
+        }
+        if let Err(e) = write_json_atomic(&index_path, &index) {
+            println!("🦀 Rust: Failed to save profiles: {}", e);
+        }
+
+        println!("🦀 Rust: Using profile: {}", name);
+        Self {
+            dir: Some(profile_dir(&root, &name)),
+            name,
+            root: Some(root),
+            index: Mutex::new(index),
+        }
+    }
+
+    /// Директория данных webview профиля. Для профиля по умолчанию остаётся
+    /// общее хранилище webview, чтобы не потерять существующие cookies и логины
+    pub fn webview_data_dir(&self) -> Option<PathBuf> {
+        if self.name == DEFAULT_PROFILE {
+            return None;
+        }
+        self.dir.as_ref().map(|dir| dir.join(WEBVIEW_DATA_DIR))
+    }
+
+    /// Директория данных webview профиля `name` (для удаления его данных)
+    pub fn webview_data_dir_of(&self, name: &str) -> Option<PathBuf> {
+        if name == DEFAULT_PROFILE {
+            return None;
+        }
+        self.root.as_ref().map(|root| profile_dir(root, name).join(WEBVIEW_DATA_DIR))
+    }
+
+    pub fn info(&self) -> ProfilesInfo {
+        ProfilesInfo {
+            profiles: self.index.lock().unwrap().profiles.clone(),
+            current: self.name.clone(),
+        }
+    }
+
+    fn root(&self) -> Result<&PathBuf, LimniError> {
+        self.root.as_ref().ok_or_else(|| LimniError::io("Profiles are unavailable"))
+    }
+
+    /// Применяет изменение к списку профилей и сохраняет его
+    fn update_index<F>(&self, f: F) -> Result<(), LimniError>
+    where
+        F: FnOnce(&mut ProfilesIndex) -> Result<(), LimniError>,
+    {
+        let root = self.root()?;
+        let mut index = self.index.lock().unwrap();
+        let mut updated = index.clone();
+        f(&mut updated)?;
+        write_json_atomic(&root.join(PROFILES_FILE), &updated)?;
+        *index = updated;
+        Ok(())
+    }
+
+    pub fn create(&self, name: &str) -> Result<(), LimniError> {
+        validate_profile_name(name)?;
+        let dir = profile_dir(self.root()?, name);
+        self.update_index(|index| {
+            if index.profiles.iter().any(|p| p == name) {
+                return Err(LimniError::invalid_argument(format!("Profile already exists: {}", name)));
+            }
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| LimniError::io(format!("Failed to create profile dir: {}", e)))?;
+            index.profiles.push(name.to_string());
+            Ok(())
+        })
+    }
+
+    /// Удаляет профиль вместе с его данными. Текущий профиль и профиль по умолчанию удалить нельзя
+    pub fn delete(&self, name: &str) -> Result<(), LimniError> {
+        if name == DEFAULT_PROFILE || name == self.name {
+            return Err(LimniError::invalid_argument(format!("Cannot delete profile: {}", name)));
+        }
+        validate_profile_name(name)?;
+        let dir = profile_dir(self.root()?, name);
+        self.update_index(|index| {
+            let before = index.profiles.len();
+            index.profiles.retain(|p| p != name);
+            if index.profiles.len() == before {
+                return Err(LimniError::not_found("Profile", name));
+            }
+            if index.last_used.as_deref() == Some(name) {
+                index.last_used = None;
+            }
+            Ok(())
+        })?;
+
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| LimniError::io(format!("Failed to delete profile data: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Запоминает профиль для следующего запуска
+    pub fn set_last_used(&self, name: &str) -> Result<(), LimniError> {
+        self.update_index(|index| {
+            if !index.profiles.iter().any(|p| p == name) {
+                return Err(LimniError::not_found("Profile", name));
+            }
+            index.last_used = Some(name.to_string());
+            Ok(())
+        })
+    }
+}
+
+/// Имя профиля текущего запуска
+pub fn current_name(app: &AppHandle) -> String {
+    app.try_state::<ProfileState>()
+        .map(|state| state.name.clone())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// Директория данных webview текущего профиля (`None` — общее хранилище по умолчанию)
+pub fn webview_data_dir(app: &AppHandle) -> Result<Option<PathBuf>, LimniError> {
+    let Some(dir) = app.try_state::<ProfileState>().and_then(|state| state.webview_data_dir()) else {
+        return Ok(None);
+    };
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| LimniError::io(format!("Failed to create webview data dir: {}", e)))?;
+    Ok(Some(dir))
+}
+
+/// Перезапускает приложение в другом профиле. Состояния истории, закладок, настроек,
+/// сессии и загрузок регистрируются в `setup` один раз вместе с путями к файлам профиля
+/// и не заменяются до выхода, поэтому профиль меняется только перезапуском
+pub fn switch_profile_impl(app: &AppHandle, name: &str) -> Result<(), LimniError> {
+    let state = app.state::<ProfileState>();
+    if name == state.name {
+        return Ok(());
+    }
+    if name != DEFAULT_PROFILE {
+        crate::data_store::ensure_separate_storage()?;
+    }
+    state.set_last_used(name)?;
+
+    // RunEvent::Exit при перезапуске не наступает, поэтому сохраняем данные сами
+    crate::flush_before_exit(app);
+    let mut env = app.env();
+    env.args_os = replace_profile_arg(&env.args_os, name);
+    println!("🦀 Rust: Restarting in profile: {}", name);
+    app.cleanup_before_exit();
+    tauri::process::restart(&env)
+}
+
+#[cfg(test)]
+mod tests;
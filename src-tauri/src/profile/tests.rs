@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn test_validate_profile_name() {
+    assert!(validate_profile_name("work").is_ok());
+    assert!(validate_profile_name("personal_2-test").is_ok());
+    assert!(validate_profile_name("").is_err());
+    assert!(validate_profile_name("../etc").is_err());
+    assert!(validate_profile_name("with space").is_err());
+    assert!(validate_profile_name(&"a".repeat(MAX_PROFILE_NAME_LEN + 1)).is_err());
+}
+
+#[test]
+fn test_profile_from_args() {
+    assert_eq!(profile_from_args(["--profile", "work"]), Some("work".to_string()));
+    assert_eq!(profile_from_args(["--verbose", "--profile=test"]), Some("test".to_string()));
+    assert_eq!(profile_from_args(["--profile"]), None);
+    assert_eq!(profile_from_args(["--profiles=x"]), None);
+    assert_eq!(profile_from_args(Vec::<String>::new()), None);
+}
+
+#[test]
+fn test_replace_profile_arg() {
+    let args: Vec<OsString> = ["limni", "--profile", "work", "--verbose", "--profile=old"]
+        .iter()
+        .map(OsString::from)
+        .collect();
+    let replaced = replace_profile_arg(&args, "personal");
+    assert_eq!(replaced, ["limni", "--verbose", "--profile", "personal"].map(OsString::from));
+}
+
+#[test]
+fn test_default_profile_uses_data_root() {
+    let root = std::path::Path::new("/data");
+    assert_eq!(profile_dir(root, DEFAULT_PROFILE), root);
+    assert_eq!(profile_dir(root, "work"), root.join("profiles").join("work"));
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use url::Host;
+
+/// Public Suffix List (https://publicsuffix.org), поставляется вместе с приложением
+const PUBLIC_SUFFIX_LIST: &str = include_str!("../data/public_suffix_list.dat");
+
+static SUFFIXES: LazyLock<SuffixList> = LazyLock::new(|| SuffixList::parse(PUBLIC_SUFFIX_LIST));
+
+/// Правила списка публичных суффиксов
+#[derive(Debug, Default)]
+pub struct SuffixList {
+    /// Обычные правила: `com`, `co.uk`
+    rules: HashSet<String>,
+    /// Правила `*.ck` хранятся без `*.`
+    wildcards: HashSet<String>,
+    /// Исключения `!www.ck` хранятся без `!`
+    exceptions: HashSet<String>,
+}
+
+/// Имя в виде, в котором его отдаёт `Url::host_str`: ASCII, IDN в punycode
+pub fn ascii_domain(name: &str) -> Option<String> {
+    match Host::parse(name).ok()? {
+        Host::Domain(domain) => Some(domain),
+        _ => None,
+    }
+}
+
+impl SuffixList {
+    /// Разбирает список в формате publicsuffix.org: правило — первое слово строки,
+    /// строки `//` — комментарии
+    pub fn parse(list: &str) -> Self {
+        let mut suffixes = Self::default();
+        for line in list.lines() {
+            let Some(rule) = line.split_whitespace().next().filter(|rule| !rule.starts_with("//")) else {
+                continue;
+            };
+            if let Some(rule) = rule.strip_prefix('!') {
+                suffixes.exceptions.extend(ascii_domain(rule));
+            } else if let Some(rule) = rule.strip_prefix("*.") {
+                suffixes.wildcards.extend(ascii_domain(rule));
+            } else {
+                suffixes.rules.extend(ascii_domain(rule));
+            }
+        }
+        suffixes
+    }
+
+    /// Число меток публичного суффикса имени. Без подходящего правила
+    /// суффиксом считается последняя метка (правило `*`)
+    fn suffix_labels(&self, labels: &[&str]) -> usize {
+        for start in 0..labels.len() {
+            let candidate = labels[start..].join(".");
+            if self.exceptions.contains(&candidate) {
+                return labels.len() - start - 1;
+            }
+            let parent = labels.get(start + 1..).filter(|rest| !rest.is_empty()).map(|rest| rest.join("."));
+            if self.rules.contains(&candidate) || parent.is_some_and(|parent| self.wildcards.contains(&parent)) {
+                return labels.len() - start;
+            }
+        }
+        1
+    }
+
+    /// Регистрируемый домен (публичный суффикс и одна метка перед ним):
+    /// `www.bbc.co.uk` -> `bbc.co.uk`. `None` для IP адресов и самих суффиксов
+    pub fn registrable_domain(&self, host: &str) -> Option<String> {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        if host.is_empty() || host.parse::<std::net::IpAddr>().is_ok() || host.starts_with('[') {
+            return None;
+        }
+        let labels: Vec<&str> = host.split('.').collect();
+        if labels.iter().any(|label| label.is_empty()) {
+            return None;
+        }
+        let suffix = self.suffix_labels(&labels);
+        (labels.len() > suffix).then(|| labels[labels.len() - suffix - 1..].join("."))
+    }
+}
+
+/// Регистрируемый домен хоста по встроенному списку публичных суффиксов
+pub fn registrable_domain(host: &str) -> Option<String> {
+    SUFFIXES.registrable_domain(host)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn test_registrable_domain_uses_longest_rule() {
+    assert_eq!(registrable_domain("www.example.com").as_deref(), Some("example.com"));
+    assert_eq!(registrable_domain("news.bbc.co.uk").as_deref(), Some("bbc.co.uk"));
+    assert_eq!(registrable_domain("Mail.Google.COM.").as_deref(), Some("google.com"));
+    assert_eq!(registrable_domain("user.github.io").as_deref(), Some("user.github.io"));
+    // Зоны без правила считаются одной меткой
+    assert_eq!(registrable_domain("notgoogle.com.evil").as_deref(), Some("com.evil"));
+}
+
+#[test]
+fn test_wildcard_and_exception_rules() {
+    let list = SuffixList::parse("// comment\ncom\n*.ck\n!www.ck\n\n公司.cn\n");
+    assert_eq!(list.registrable_domain("a.b.co.ck").as_deref(), Some("b.co.ck"));
+    assert_eq!(list.registrable_domain("www.ck").as_deref(), Some("www.ck"));
+    assert_eq!(list.registrable_domain("shop.www.ck").as_deref(), Some("www.ck"));
+    assert_eq!(list.registrable_domain("co.ck"), None);
+    assert_eq!(list.registrable_domain("www.shop.xn--55qx5d.cn").as_deref(), Some("shop.xn--55qx5d.cn"));
+}
+
+#[test]
+fn test_suffixes_and_addresses_have_no_registrable_domain() {
+    assert_eq!(registrable_domain("com"), None);
+    assert_eq!(registrable_domain("co.uk"), None);
+    assert_eq!(registrable_domain("127.0.0.1"), None);
+    assert_eq!(registrable_domain("[::1]"), None);
+    assert_eq!(registrable_domain("a..com"), None);
+    assert_eq!(registrable_domain(""), None);
+}
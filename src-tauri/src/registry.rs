@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Webview};
+use tokio::sync::{mpsc, oneshot};
+use crate::error::LimniError;
+use crate::utils::now_millis;
+
+/// Состояние загрузки страницы вкладки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LoadState {
+    Loading,
+    Loaded,
+    /// Загрузка не удалась (сервер не ответил или ответил ошибкой 5xx)
+    Failed,
+}
+
+/// Откуда взят title вкладки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TitleSource {
+    /// Предварительный title из URL, пока страница не сообщила свой
+    Url,
+    /// `document.title` текущей страницы
+    Page,
+}
+
+/// Всё, что бэкенд знает о вкладке
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabRecord {
+    pub tab_id: String,
+    pub webview_label: String,
+    pub window_label: String,
+    pub container_id: Option<String>,
+    pub url: String,
+    pub title: String,
+    pub title_source: TitleSource,
+    /// Номер навигации вкладки, увеличивается при каждом переходе
+    pub navigation_id: u64,
+    pub favicon: Option<String>,
+    pub has_audio: bool,
+    pub muted: bool,
+    pub load_state: LoadState,
+    /// Когда началась последняя загрузка страницы
+    pub load_started_at: Option<u64>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    /// Когда вкладка последний раз была показана (`None` — ещё не показывалась)
+    pub last_active_at: Option<u64>,
+}
+
+impl TabRecord {
+    pub fn new(tab_id: &str, webview_label: &str, window_label: &str, url: &str, title: &str, now: u64) -> Self {
+        Self {
+            tab_id: tab_id.to_string(),
+            webview_label: webview_label.to_string(),
+            window_label: window_label.to_string(),
+            container_id: None,
+            url: url.to_string(),
+            title: title.to_string(),
+            title_source: TitleSource::Url,
+            navigation_id: 0,
+            favicon: None,
+            has_audio: false,
+            muted: false,
+            load_state: LoadState::Loading,
+            load_started_at: None,
+            created_at: now,
+            updated_at: now,
+            last_active_at: None,
+        }
+    }
+
+    /// Начинает новую навигацию: до ответа страницы показываем title из URL
+    pub fn begin_navigation(&mut self, url: &str, url_title: &str) {
+        self.navigation_id += 1;
+        self.url = url.to_string();
+        self.load_state = LoadState::Loading;
+        self.title = url_title.to_string();
+        self.title_source = TitleSource::Url;
+    }
+
+    /// Применяет title, сообщённый страницей с адресом `page_url`.
+    /// Пока идёт навигация, сообщения со старым адресом приходят от уходящего
+    /// документа и игнорируются. После загрузки адрес может меняться без навигации
+    /// (history API), поэтому там принимается любой. Возвращает `true`, если title изменился
+    pub fn apply_page_title(&mut self, page_url: &str, title: &str) -> bool {
+        let title = normalize_title(title);
+        if title.is_empty() {
+            return false;
+        }
+        if self.load_state == LoadState::Loading && without_fragment(page_url) != without_fragment(&self.url) {
+            return false;
+        }
+        let changed = self.title != title;
+        self.title = title;
+        self.title_source = TitleSource::Page;
+        changed
+    }
+
+    /// Применяет title из URL, если страница ещё не сообщила свой
+    pub fn apply_fallback_title(&mut self, title: &str) -> bool {
+        if self.title_source == TitleSource::Page || self.title == title {
+            return false;
+        }
+        self.title = title.to_string();
+        true
+    }
+}
+
+/// Схлопывает пробельные символы title в одиночные пробелы
+pub fn normalize_title(title: &str) -> String {
+    title.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn without_fragment(url: &str) -> &str {
+    url.split_once('#').map_or(url, |(base, _)| base)
+}
+
+/// Таблица вкладок. Принадлежит задаче реестра и снаружи не используется
+#[derive(Default)]
+pub struct TabTable {
+    tabs: HashMap<String, TabRecord>,
+}
+
+impl TabTable {
+    pub fn insert(&mut self, record: TabRecord) {
+        self.tabs.insert(record.tab_id.clone(), record);
+    }
+
+    pub fn remove(&mut self, tab_id: &str) -> Option<TabRecord> {
+        self.tabs.remove(tab_id)
+    }
+
+    pub fn get(&self, tab_id: &str) -> Option<TabRecord> {
+        self.tabs.get(tab_id).cloned()
+    }
+
+    /// Применяет изменение к вкладке и обновляет `updated_at`
+    pub fn update(&mut self, tab_id: &str, f: impl FnOnce(&mut TabRecord), now: u64) -> Option<TabRecord> {
+        let record = self.tabs.get_mut(tab_id)?;
+        f(record);
+        record.updated_at = now;
+        Some(record.clone())
+    }
+
+    /// Вкладки окна в порядке создания
+    pub fn in_window(&self, window_label: &str) -> Vec<TabRecord> {
+        let mut records: Vec<TabRecord> = self.tabs
+            .values()
+            .filter(|r| r.window_label == window_label)
+            .cloned()
+            .collect();
+        records.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.tab_id.cmp(&b.tab_id)));
+        records
+    }
+
+    pub fn all(&self) -> Vec<TabRecord> {
+        let mut records: Vec<TabRecord> = self.tabs.values().cloned().collect();
+        records.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.tab_id.cmp(&b.tab_id)));
+        records
+    }
+}
+
+type Updater = Box<dyn FnOnce(&mut TabRecord) + Send>;
+
+/// Сообщения задаче реестра
+enum Message {
+    Insert(TabRecord),
+    Update { tab_id: String, f: Updater },
+    Remove { tab_id: String, reply: oneshot::Sender<Option<TabRecord>> },
+    Get { tab_id: String, reply: oneshot::Sender<Option<TabRecord>> },
+    InWindow { window_label: String, reply: oneshot::Sender<Vec<TabRecord>> },
+    All { reply: oneshot::Sender<Vec<TabRecord>> },
+}
+
+/// Реестр вкладок: единственная задача владеет записями вкладок,
+/// остальной код общается с ней сообщениями и никогда не держит блокировку
+/// во время вызовов webview API. Сообщения обрабатываются по порядку,
+/// поэтому запрос после `insert`/`update` всегда видит их результат
+#[derive(Clone)]
+pub struct TabRegistry {
+    sender: mpsc::UnboundedSender<Message>,
+}
+
+impl Default for TabRegistry {
+    fn default() -> Self {
+        Self::spawn()
+    }
+}
+
+impl TabRegistry {
+    /// Запускает задачу реестра
+    pub fn spawn() -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(async move {
+            let mut table = TabTable::default();
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    Message::Insert(record) => table.insert(record),
+                    Message::Update { tab_id, f } => {
+                        table.update(&tab_id, f, now_millis());
+                    }
+                    Message::Remove { tab_id, reply } => {
+                        let _ = reply.send(table.remove(&tab_id));
+                    }
+                    Message::Get { tab_id, reply } => {
+                        let _ = reply.send(table.get(&tab_id));
+                    }
+                    Message::InWindow { window_label, reply } => {
+                        let _ = reply.send(table.in_window(&window_label));
+                    }
+                    Message::All { reply } => {
+                        let _ = reply.send(table.all());
+                    }
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    fn send(&self, message: Message) {
+        // Задача реестра живёт столько же, сколько приложение
+        let _ = self.sender.send(message);
+    }
+
+    async fn request<T: Default>(&self, make: impl FnOnce(oneshot::Sender<T>) -> Message) -> T {
+        let (reply, response) = oneshot::channel();
+        self.send(make(reply));
+        response.await.unwrap_or_default()
+    }
+
+    /// Регистрирует вкладку (не ждёт ответа, можно вызывать из синхронного кода)
+    pub fn insert(&self, record: TabRecord) {
+        self.send(Message::Insert(record));
+    }
+
+    /// Изменяет запись вкладки (не ждёт ответа, можно вызывать из синхронного кода)
+    pub fn update(&self, tab_id: &str, f: impl FnOnce(&mut TabRecord) + Send + 'static) {
+        self.send(Message::Update { tab_id: tab_id.to_string(), f: Box::new(f) });
+    }
+
+    /// Изменяет запись вкладки и возвращает результат изменения (`None`, если вкладки нет)
+    pub async fn modify<T: Send + 'static>(
+        &self,
+        tab_id: &str,
+        f: impl FnOnce(&mut TabRecord) -> T + Send + 'static,
+    ) -> Option<T> {
+        let (reply, response) = oneshot::channel();
+        self.update(tab_id, move |record| {
+            let _ = reply.send(f(record));
+        });
+        response.await.ok()
+    }
+
+    pub async fn remove(&self, tab_id: &str) -> Option<TabRecord> {
+        let tab_id = tab_id.to_string();
+        self.request(|reply| Message::Remove { tab_id, reply }).await
+    }
+
+    pub async fn get(&self, tab_id: &str) -> Option<TabRecord> {
+        let tab_id = tab_id.to_string();
+        self.request(|reply| Message::Get { tab_id, reply }).await
+    }
+
+    pub async fn in_window(&self, window_label: &str) -> Vec<TabRecord> {
+        let window_label = window_label.to_string();
+        self.request(|reply| Message::InWindow { window_label, reply }).await
+    }
+
+    pub async fn all(&self) -> Vec<TabRecord> {
+        self.request(|reply| Message::All { reply }).await
+    }
+}
+
+/// Реестр вкладок приложения
+pub fn registry(app: &AppHandle) -> TabRegistry {
+    app.state::<TabRegistry>().inner().clone()
+}
+
+/// Webview вкладки: `TabNotFound`, если вкладка неизвестна,
+/// и `WebviewGone`, если вкладка есть, а её webview уже нет
+pub async fn tab_webview(app: &AppHandle, tab_id: &str) -> Result<Webview, LimniError> {
+    let record = registry(app).get(tab_id).await
+        .ok_or_else(|| LimniError::tab_not_found(tab_id))?;
+    app.get_webview(&record.webview_label)
+        .ok_or_else(|| LimniError::webview_gone(tab_id))
+}
+
+#[cfg(test)]
+mod tests;
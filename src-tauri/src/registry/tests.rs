@@ -0,0 +1,82 @@
+use super::*;
+
+fn record(tab_id: &str, window_label: &str, created_at: u64) -> TabRecord {
+    TabRecord::new(tab_id, &format!("tab-{}", tab_id), window_label, "https://example.com/", "Example", created_at)
+}
+
+#[test]
+fn test_table_filters_by_window_in_creation_order() {
+    let mut table = TabTable::default();
+    table.insert(record("b", "main", 2));
+    table.insert(record("a", "main", 1));
+    table.insert(record("c", "window-1", 3));
+
+    let ids: Vec<String> = table.in_window("main").into_iter().map(|r| r.tab_id).collect();
+    assert_eq!(ids, vec!["a", "b"]);
+    assert_eq!(table.all().len(), 3);
+    assert!(table.remove("c").is_some());
+    assert!(table.in_window("window-1").is_empty());
+}
+
+#[test]
+fn test_table_update_touches_timestamp() {
+    let mut table = TabTable::default();
+    table.insert(record("a", "main", 1));
+
+    let updated = table.update("a", |r| r.muted = true, 50).unwrap();
+    assert!(updated.muted);
+    assert_eq!(updated.updated_at, 50);
+    assert!(table.update("missing", |r| r.muted = true, 60).is_none());
+}
+
+#[test]
+fn test_registry_processes_messages_in_order() {
+    let registry = TabRegistry::spawn();
+    registry.insert(record("a", "main", 1));
+    registry.update("a", |r| r.title = "Docs".to_string());
+
+    tauri::async_runtime::block_on(async {
+        assert_eq!(registry.get("a").await.unwrap().title, "Docs");
+        assert_eq!(registry.in_window("main").await.len(), 1);
+        assert!(registry.remove("a").await.is_some());
+        assert!(registry.get("a").await.is_none());
+    });
+}
+
+#[test]
+fn test_page_title_wins_over_url_title_for_navigation() {
+    let mut tab = record("a", "main", 1);
+    tab.begin_navigation("https://example.com/docs", "example.com");
+    assert_eq!(tab.navigation_id, 1);
+    assert_eq!(tab.title_source, TitleSource::Url);
+
+    assert!(tab.apply_page_title("https://example.com/docs#intro", "  Docs\n  Home "));
+    assert_eq!(tab.title, "Docs Home");
+    assert!(!tab.apply_fallback_title("example.com"));
+    assert!(!tab.apply_page_title("https://example.com/docs", "Docs Home"));
+    assert!(!tab.apply_page_title("https://example.com/docs", "   "));
+    assert_eq!(tab.title, "Docs Home");
+}
+
+#[test]
+fn test_page_title_from_previous_document_is_ignored_while_loading() {
+    let mut tab = record("a", "main", 1);
+    tab.begin_navigation("https://example.com/next", "example.com");
+    assert!(!tab.apply_page_title("https://example.com/", "Old page"));
+    assert!(tab.apply_fallback_title("Example"));
+
+    // После загрузки адрес может смениться через history API
+    tab.load_state = LoadState::Loaded;
+    assert!(tab.apply_page_title("https://example.com/next/step-2", "Step 2"));
+}
+
+#[test]
+fn test_modify_returns_closure_result() {
+    let registry = TabRegistry::spawn();
+    registry.insert(record("a", "main", 1));
+
+    tauri::async_runtime::block_on(async {
+        assert_eq!(registry.modify("a", |r| r.apply_fallback_title("Example")).await, Some(false));
+        assert_eq!(registry.modify("missing", |r| r.apply_fallback_title("x")).await, None);
+    });
+}
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use crate::error::LimniError;
+use crate::storage::{data_dir, read_json_with_backup, write_json_with_backup};
+use crate::state::MAIN_WINDOW_LABEL;
+use crate::utils::now_millis;
+use crate::webview::{create_tab_webview_impl, new_window_label, open_browser_window, set_tab_muted, webview_label_for};
+
+/// Имя файла сессии в директории данных приложения
+const SESSION_FILE: &str = "session.json";
+
+/// Интервал периодического сохранения сессии
+const AUTOSAVE_INTERVAL_SECS: u64 = 10;
+
+/// Вкладка в снимке сессии
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTab {
+    pub tab_id: String,
+    pub url: String,
+    pub title: String,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub container_id: Option<String>,
+    /// Окно, в котором открыта вкладка. Снимки без окон относятся к главному окну
+    #[serde(default = "main_window_label")]
+    pub window_label: String,
+}
+
+fn main_window_label() -> String {
+    MAIN_WINDOW_LABEL.to_string()
+}
+
+/// Снимок открытых вкладок всех окон (внутри окна — в порядке отображения)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub tabs: Vec<SessionTab>,
+    /// Активная вкладка каждого окна
+    #[serde(default)]
+    pub active_tab_ids: HashMap<String, String>,
+    pub saved_at: u64,
+}
+
+/// Вкладки одного окна прошлого запуска
+#[derive(Debug, Clone, Default)]
+pub struct SessionWindow {
+    pub tabs: Vec<SessionTab>,
+    pub active_tab_id: Option<String>,
+}
+
+impl Session {
+    fn tab_mut(&mut self, tab_id: &str) -> Option<&mut SessionTab> {
+        self.tabs.iter_mut().find(|t| t.tab_id == tab_id)
+    }
+
+    /// Добавляет вкладку окна в конец (или обновляет URL уже известной вкладки)
+    pub fn open_tab(&mut self, window_label: &str, tab_id: &str, url: &str, title: &str) {
+        match self.tab_mut(tab_id) {
+            Some(tab) => {
+                tab.url = url.to_string();
+                tab.window_label = window_label.to_string();
+            }
+            None => self.tabs.push(SessionTab {
+                tab_id: tab_id.to_string(),
+                url: url.to_string(),
+                title: title.to_string(),
+                pinned: false,
+                muted: false,
+                container_id: None,
+                window_label: window_label.to_string(),
+            }),
+        }
+    }
+
+    pub fn close_tab(&mut self, tab_id: &str) {
+        self.tabs.retain(|t| t.tab_id != tab_id);
+        self.active_tab_ids.retain(|_, active| active != tab_id);
+    }
+
+    pub fn set_url(&mut self, tab_id: &str, url: &str) {
+        if let Some(tab) = self.tab_mut(tab_id) {
+            tab.url = url.to_string();
+        }
+    }
+
+    pub fn set_title(&mut self, tab_id: &str, title: &str) {
+        if let Some(tab) = self.tab_mut(tab_id) {
+            tab.title = title.to_string();
+        }
+    }
+
+    pub fn set_active(&mut self, tab_id: &str) {
+        if let Some(tab) = self.tabs.iter().find(|t| t.tab_id == tab_id) {
+            self.active_tab_ids.insert(tab.window_label.clone(), tab_id.to_string());
+        }
+    }
+
+    pub fn set_muted(&mut self, tab_id: &str, muted: bool) {
+        if let Some(tab) = self.tab_mut(tab_id) {
+            tab.muted = muted;
+        }
+    }
+
+    pub fn set_pinned(&mut self, tab_id: &str, pinned: bool) {
+        if let Some(tab) = self.tab_mut(tab_id) {
+            tab.pinned = pinned;
+        }
+    }
+
+    pub fn set_container(&mut self, tab_id: &str, container_id: Option<&str>) {
+        if let Some(tab) = self.tab_mut(tab_id) {
+            tab.container_id = container_id.map(str::to_string);
+        }
+    }
+
+    /// Упорядочивает вкладки по списку id; неизвестные id игнорируются,
+    /// вкладки, не попавшие в список, остаются в конце в прежнем порядке
+    pub fn reorder(&mut self, tab_ids: &[String]) {
+        let mut ordered = Vec::with_capacity(self.tabs.len());
+        for tab_id in tab_ids {
+            if let Some(index) = self.tabs.iter().position(|t| &t.tab_id == tab_id) {
+                ordered.push(self.tabs.remove(index));
+            }
+        }
+        ordered.append(&mut self.tabs);
+        self.tabs = ordered;
+    }
+
+    /// Разбивает снимок по окнам в порядке их первого упоминания
+    pub fn into_windows(self) -> Vec<(String, SessionWindow)> {
+        let mut windows: Vec<(String, SessionWindow)> = Vec::new();
+        for tab in self.tabs {
+            let index = match windows.iter().position(|(label, _)| label == &tab.window_label) {
+                Some(index) => index,
+                None => {
+                    let active_tab_id = self.active_tab_ids.get(&tab.window_label).cloned();
+                    windows.push((tab.window_label.clone(), SessionWindow { tabs: Vec::new(), active_tab_id }));
+                    windows.len() - 1
+                }
+            };
+            windows[index].1.tabs.push(tab);
+        }
+        windows
+    }
+
+    /// Добавляет вкладки восстановленного окна перед вкладками, открытыми до восстановления.
+    /// Уже известные вкладки не перезаписываются
+    pub fn merge_window(&mut self, window_label: &str, window: &SessionWindow) {
+        let restored: Vec<SessionTab> = window.tabs
+            .iter()
+            .filter(|tab| self.tabs.iter().all(|t| t.tab_id != tab.tab_id))
+            .map(|tab| SessionTab { window_label: window_label.to_string(), ..tab.clone() })
+            .collect();
+        self.tabs.splice(0..0, restored);
+        if let Some(active) = &window.active_tab_id {
+            self.set_active(active);
+        }
+    }
+}
+
+/// Вкладка восстановленной сессии вместе с меткой её webview
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredTab {
+    #[serde(flatten)]
+    pub tab: SessionTab,
+    pub webview_label: String,
+    /// Вкладка была активной; её webview уже создан
+    pub active: bool,
+}
+
+/// Состояние сессии: текущий снимок, сессия прошлого запуска и отложенные вкладки
+#[derive(Default)]
+pub struct SessionState {
+    path: Option<PathBuf>,
+    pub current: Mutex<Session>,
+    dirty: AtomicBool,
+    /// Frontend уже забрал сессию прошлого запуска. До этого снимок не сохраняется:
+    /// первая вкладка нового запуска иначе перезаписала бы прошлую сессию и её копию
+    restored: AtomicBool,
+    /// Сессия прошлого запуска, которую ещё не забрал frontend
+    previous: Mutex<Option<Session>>,
+    /// Окна прошлого запуска, открытые заново, но ещё не забравшие свои вкладки
+    pending_windows: Mutex<HashMap<String, SessionWindow>>,
+    /// Восстановленные вкладки, webview которых создаётся только при первом показе
+    pub lazy_tabs: Mutex<HashMap<String, SessionTab>>,
+}
+
+impl SessionState {
+    /// Загружает сессию прошлого запуска (или её резервную копию)
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match data_dir(app) {
+            Ok(dir) => dir.join(SESSION_FILE),
+            Err(e) => {
+                println!("🦀 Rust: Session will not be persisted: {}", e);
+                return Self::default();
+            }
+        };
+
+        let previous: Session = read_json_with_backup(&path).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load session: {}", e);
+            Session::default()
+        });
+
+        Self {
+            path: Some(path),
+            previous: Mutex::new(Some(previous).filter(|s| !s.tabs.is_empty())),
+            ..Self::default()
+        }
+    }
+
+    /// Применяет изменение к текущему снимку и помечает его для сохранения
+    pub fn update<F: FnOnce(&mut Session)>(&self, f: F) {
+        let mut session = self.current.lock().unwrap();
+        f(&mut session);
+        self.dirty.store(true, Ordering::SeqCst);
+    }
+
+    /// Забирает сессию прошлого запуска (один раз) и разрешает сохранять текущий снимок
+    pub fn take_previous(&self) -> Option<Session> {
+        let previous = self.previous.lock().unwrap().take();
+        self.restored.store(true, Ordering::SeqCst);
+        previous
+    }
+
+    /// Сохраняет снимок, если с прошлого сохранения были изменения
+    pub fn save_if_dirty(&self) -> Result<(), LimniError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.restored.load(Ordering::SeqCst) || !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let mut session = self.current.lock().unwrap().clone();
+        session.saved_at = now_millis();
+        write_json_with_backup(path, &session).inspect_err(|_| {
+            // Повторим попытку при следующем сохранении
+            self.dirty.store(true, Ordering::SeqCst);
+        })
+    }
+}
+
+/// Обновляет снимок сессии, если состояние сессии уже зарегистрировано
+pub fn update<F: FnOnce(&mut Session)>(app: &AppHandle, f: F) {
+    if let Some(state) = app.try_state::<SessionState>() {
+        state.update(f);
+    }
+}
+
+/// Забирает отложенную (ещё не созданную) вкладку восстановленной сессии
+pub fn take_lazy_tab(app: &AppHandle, tab_id: &str) -> Option<SessionTab> {
+    let state = app.try_state::<SessionState>()?;
+    let mut lazy_tabs = state.lazy_tabs.lock().unwrap();
+    lazy_tabs.remove(tab_id)
+}
+
+/// Сохраняет сессию немедленно (например, при выходе из приложения)
+pub fn save_now(app: &AppHandle) {
+    if let Some(state) = app.try_state::<SessionState>() {
+        if let Err(e) = state.save_if_dirty() {
+            println!("🦀 Rust: Failed to save session: {}", e);
+        }
+    }
+}
+
+/// Восстанавливает вкладки окна из сессии прошлого запуска. Первое окно, запросившее
+/// сессию, получает свои вкладки (или вкладки первого сохранённого окна), для остальных
+/// сохранённых окон открываются новые окна, которые заберут вкладки сами.
+/// Webview создаётся только для активной вкладки, остальные вкладки создаются
+/// при первом показе через `show_tab_webview`. Приватные окна сессию не восстанавливают и не забирают
+pub async fn restore_session_impl(app: AppHandle, window_label: String) -> Result<Vec<RestoredTab>, LimniError> {
+    if crate::private::is_private_window(&window_label) {
+        return Ok(Vec::new());
+    }
+    let state = app.state::<SessionState>();
+    if let Some(previous) = state.take_previous() {
+        let mut windows = previous.into_windows();
+        let own = windows.iter().position(|(label, _)| label == &window_label).unwrap_or(0);
+        let (_, own_window) = windows.remove(own);
+        state.pending_windows.lock().unwrap().insert(window_label.clone(), own_window);
+
+        for (_, window) in windows {
+            let label = new_window_label(false);
+            state.pending_windows.lock().unwrap().insert(label.clone(), window);
+            if let Err(e) = open_browser_window(&app, &label) {
+                println!("🦀 Rust: Failed to restore window: {}", e);
+                state.pending_windows.lock().unwrap().remove(&label);
+            }
+        }
+    }
+
+    let pending = state.pending_windows.lock().unwrap().remove(&window_label);
+    let Some(mut window) = pending else {
+        return Ok(Vec::new());
+    };
+    for tab in &mut window.tabs {
+        tab.window_label = window_label.clone();
+    }
+    window.active_tab_id = window.active_tab_id
+        .take()
+        .filter(|id| window.tabs.iter().any(|t| &t.tab_id == id))
+        .or_else(|| window.tabs.first().map(|t| t.tab_id.clone()));
+    let active_tab_id = window.active_tab_id.clone();
+
+    // Переносим порядок, закрепление и звук в текущий снимок
+    state.update(|session| session.merge_window(&window_label, &window));
+
+    {
+        let mut lazy_tabs = state.lazy_tabs.lock().unwrap();
+        for tab in window.tabs.iter().filter(|t| Some(&t.tab_id) != active_tab_id.as_ref()) {
+            lazy_tabs.insert(tab.tab_id.clone(), tab.clone());
+        }
+    }
+
+    if let Some(active) = window.tabs.iter().find(|t| Some(&t.tab_id) == active_tab_id.as_ref()) {
+        create_tab_webview_impl(app.clone(), window_label.clone(), active.tab_id.clone(), active.url.clone(), active.title.clone(), active.container_id.clone()).await?;
+        if active.muted {
+            set_tab_muted(&app, &active.tab_id, true).await?;
+        }
+    }
+
+    println!("🦀 Rust: Restored {} tabs in window {}", window.tabs.len(), window_label);
+    Ok(window.tabs
+        .into_iter()
+        .map(|tab| RestoredTab {
+            webview_label: webview_label_for(&tab.tab_id),
+            active: Some(&tab.tab_id) == active_tab_id.as_ref(),
+            tab,
+        })
+        .collect())
+}
+
+/// Запускает периодическое сохранение снимка сессии
+pub fn start_autosave(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(AUTOSAVE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let app_for_save = app.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || save_now(&app_for_save)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests;
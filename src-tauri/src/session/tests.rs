@@ -0,0 +1,97 @@
+use super::*;
+
+fn tab_ids(session: &Session) -> Vec<&str> {
+    session.tabs.iter().map(|t| t.tab_id.as_str()).collect()
+}
+
+#[test]
+fn test_open_close_and_active_tab() {
+    let mut session = Session::default();
+    session.open_tab("main", "a", "https://a.com/", "A");
+    session.open_tab("main", "b", "https://b.com/", "B");
+    session.open_tab("main", "a", "https://a.com/next", "ignored");
+    session.set_active("b");
+    session.set_active("missing");
+
+    assert_eq!(tab_ids(&session), vec!["a", "b"]);
+    assert_eq!(session.tabs[0].url, "https://a.com/next");
+    assert_eq!(session.tabs[0].title, "A");
+    assert_eq!(session.active_tab_ids.get("main").map(String::as_str), Some("b"));
+
+    session.close_tab("b");
+    assert_eq!(tab_ids(&session), vec!["a"]);
+    assert!(session.active_tab_ids.is_empty());
+}
+
+#[test]
+fn test_session_splits_into_windows() {
+    let mut session = Session::default();
+    session.open_tab("main", "a", "https://a.com/", "A");
+    session.open_tab("window-2", "b", "https://b.com/", "B");
+    session.open_tab("main", "c", "https://c.com/", "C");
+    session.set_active("c");
+    session.set_active("b");
+
+    let windows = session.into_windows();
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows[0].0, "main");
+    assert_eq!(windows[0].1.tabs.iter().map(|t| t.tab_id.as_str()).collect::<Vec<_>>(), vec!["a", "c"]);
+    assert_eq!(windows[0].1.active_tab_id.as_deref(), Some("c"));
+    assert_eq!(windows[1].1.active_tab_id.as_deref(), Some("b"));
+}
+
+#[test]
+fn test_old_snapshot_tabs_belong_to_main_window() {
+    let session: Session = serde_json::from_value(serde_json::json!({
+        "tabs": [{ "tabId": "a", "url": "https://a.com/", "title": "A" }],
+        "savedAt": 1
+    })).unwrap();
+    assert_eq!(session.tabs[0].window_label, "main");
+}
+
+#[test]
+fn test_merge_window_keeps_tabs_opened_before_restore() {
+    let mut session = Session::default();
+    session.open_tab("main", "new", "about:blank", "New Tab");
+
+    let mut previous = Session::default();
+    previous.open_tab("window-old", "a", "https://a.com/", "A");
+    previous.open_tab("window-old", "new", "https://stale.com/", "Stale");
+    previous.set_active("a");
+    let (_, window) = previous.into_windows().remove(0);
+
+    session.merge_window("main", &window);
+    assert_eq!(tab_ids(&session), vec!["a", "new"]);
+    assert_eq!(session.tabs[0].window_label, "main");
+    assert_eq!(session.tabs[1].url, "about:blank");
+    assert_eq!(session.active_tab_ids.get("main").map(String::as_str), Some("a"));
+}
+
+#[test]
+fn test_reorder_keeps_unlisted_tabs() {
+    let mut session = Session::default();
+    for id in ["a", "b", "c", "d"] {
+        session.open_tab("main", id, "https://example.com/", id);
+    }
+    session.set_pinned("c", true);
+    session.reorder(&["c".to_string(), "x".to_string(), "a".to_string()]);
+
+    assert_eq!(tab_ids(&session), vec!["c", "a", "b", "d"]);
+    assert!(session.tabs[0].pinned);
+}
+
+#[test]
+fn test_session_is_not_saved_before_restore() {
+    let dir = std::env::temp_dir().join(format!("limni_session_test_{}", now_millis()));
+    let path = dir.join(SESSION_FILE);
+    let state = SessionState { path: Some(path.clone()), ..SessionState::default() };
+
+    state.update(|s| s.open_tab("main", "a", "about:blank", "New Tab"));
+    state.save_if_dirty().unwrap();
+    assert!(!path.exists());
+
+    assert!(state.take_previous().is_none());
+    state.save_if_dirty().unwrap();
+    assert!(path.exists());
+    let _ = std::fs::remove_dir_all(dir);
+}
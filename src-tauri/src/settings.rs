@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::error::LimniError;
+use crate::favicon::fallback;
+use crate::i18n;
+use crate::storage::{data_dir, read_json, write_json_atomic};
+
+/// Имя файла настроек в директории данных приложения
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Настройки браузера (зеркало `BrowserSettings` во frontend).
+/// Отсутствующие в файле поля берутся из значений по умолчанию
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BrowserSettings {
+    pub homepage: String,
+    pub search_engine: String,
+    pub download_path: String, // пустая строка — системная папка загрузок
+    pub theme: String,
+    pub allow_java_script: bool,
+    pub allow_images: bool,
+    pub allow_cookies: bool,
+    /// HTTP(S) прокси для фоновых запросов бэкенда, пустая строка — без прокси
+    pub proxy: String,
+    /// Значок для сайтов без своего: "letter", "service" или "none"
+    pub favicon_fallback: String,
+    /// Шаблон адреса сервиса значков с `{host}`
+    pub favicon_service_url: String,
+    /// Язык интерфейса: "system" или тег языка ("en", "ru", "pt-BR")
+    pub language: String,
+}
+
+impl Default for BrowserSettings {
+    fn default() -> Self {
+        Self {
+            homepage: "https://www.google.com".to_string(),
+            search_engine: "google".to_string(),
+            download_path: String::new(),
+            theme: "system".to_string(),
+            allow_java_script: true,
+            allow_images: true,
+            allow_cookies: true,
+            proxy: String::new(),
+            favicon_fallback: fallback::FALLBACK_LETTER.to_string(),
+            favicon_service_url: fallback::DEFAULT_ICON_SERVICE.to_string(),
+            language: i18n::LANGUAGE_SYSTEM.to_string(),
+        }
+    }
+}
+
+/// Состояние настроек, разделяемое между командами
+#[derive(Default)]
+pub struct SettingsState {
+    path: Option<PathBuf>,
+    pub settings: Mutex<BrowserSettings>,
+}
+
+impl SettingsState {
+    /// Загружает настройки из директории данных приложения
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match data_dir(app) {
+            Ok(dir) => dir.join(SETTINGS_FILE),
+            Err(e) => {
+                println!("🦀 Rust: Settings will not be persisted: {}", e);
+                return Self::default();
+            }
+        };
+
+        let settings = read_json(&path).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load settings, using defaults: {}", e);
+            BrowserSettings::default()
+        });
+
+        Self { path: Some(path), settings: Mutex::new(settings) }
+    }
+
+    /// Заменяет настройки и сохраняет их на диск
+    pub fn replace(&self, settings: BrowserSettings) -> Result<(), LimniError> {
+        let mut current = self.settings.lock().unwrap();
+        *current = settings;
+        match &self.path {
+            Some(path) => write_json_atomic(path, &*current),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Возвращает копию текущих настроек
+pub fn current(app: &AppHandle) -> BrowserSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.settings.lock().unwrap().clone())
+        .unwrap_or_default()
+}
+
+/// Сохраняет новые настройки и сообщает всем окнам об изменении
+pub fn update_settings_impl(app: &AppHandle, settings: BrowserSettings) -> Result<(), LimniError> {
+    fallback::validate_settings(&settings.favicon_fallback, &settings.favicon_service_url)?;
+    i18n::validate_language(&settings.language)?;
+    // Прокси проверяется до сохранения, чтобы в файл не попало некорректное значение
+    if current(app).proxy != settings.proxy {
+        crate::net::network(app).set_proxy(&settings.proxy)?;
+    }
+    let state = app.state::<SettingsState>();
+    state.replace(settings.clone())?;
+    app.emit("settings-changed", settings)
+        .map_err(|e| LimniError::internal(format!("Failed to emit settings change event: {}", e)))
+}
@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use crate::error::LimniError;
+use crate::public_suffix::{ascii_domain, registrable_domain};
+use crate::storage::{data_dir, read_json, write_json_atomic};
+
+/// Имя файла пользовательских названий сайтов в директории данных профиля
+const SITE_NAMES_FILE: &str = "site_names.json";
+
+/// Встроенные названия сайтов: домен -> название
+const BUILTIN_SITE_NAMES: &str = include_str!("../data/site_names.json");
+
+/// Самое длинное название, которое можно задать сайту
+const MAX_SITE_NAME_CHARS: usize = 100;
+
+static BUILTIN: LazyLock<HashMap<String, String>> = LazyLock::new(|| parse_site_names(BUILTIN_SITE_NAMES));
+
+/// Разбирает таблицу названий. Домены приводятся к виду `Url::host_str`,
+/// некорректные записи пропускаются
+fn parse_site_names(json: &str) -> HashMap<String, String> {
+    let names: BTreeMap<String, String> = serde_json::from_str(json).unwrap_or_else(|e| {
+        println!("🦀 Rust: Invalid built-in site names: {}", e);
+        BTreeMap::new()
+    });
+    names.into_iter()
+        .filter_map(|(domain, name)| Some((normalize_domain(&domain)?, name)))
+        .collect()
+}
+
+/// Домен в нижнем регистре, IDN в punycode, без `www.` и точки в конце
+fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = ascii_domain(domain.trim().trim_end_matches('.'))?;
+    Some(domain.strip_prefix("www.").map(str::to_string).unwrap_or(domain))
+}
+
+fn validate_name(name: &str) -> Result<String, LimniError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(LimniError::invalid_argument("Site name cannot be empty"));
+    }
+    if name.chars().count() > MAX_SITE_NAME_CHARS {
+        return Err(LimniError::invalid_argument("Site name is too long"));
+    }
+    Ok(name.to_string())
+}
+
+/// Формат файла названий на диске
+#[derive(Default, Serialize, Deserialize)]
+struct SiteNamesFile {
+    names: BTreeMap<String, String>,
+}
+
+/// Встроенные и пользовательские названия для отдачи во frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteNames {
+    pub builtin: BTreeMap<String, String>,
+    pub overrides: BTreeMap<String, String>,
+}
+
+/// Реестр названий сайтов. Пользовательские названия важнее встроенных
+#[derive(Default)]
+pub struct SiteNameStore {
+    path: Option<PathBuf>,
+    overrides: BTreeMap<String, String>,
+}
+
+impl SiteNameStore {
+    /// Загружает пользовательские названия из файла (пустой реестр, если файла нет или он повреждён)
+    pub fn load(path: PathBuf) -> Self {
+        let file: SiteNamesFile = read_json(&path).unwrap_or_else(|e| {
+            println!("🦀 Rust: Failed to load site names, starting empty: {}", e);
+            SiteNamesFile::default()
+        });
+
+        Self { path: Some(path), overrides: file.names }
+    }
+
+    /// Сохраняет пользовательские названия на диск
+    pub fn save(&self) -> Result<(), LimniError> {
+        match &self.path {
+            Some(path) => write_json_atomic(path, &SiteNamesFile { names: self.overrides.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn names(&self) -> SiteNames {
+        SiteNames {
+            builtin: BUILTIN.iter().map(|(domain, name)| (domain.clone(), name.clone())).collect(),
+            overrides: self.overrides.clone(),
+        }
+    }
+
+    /// Задаёт название сайта (`None` — вернуть встроенное или имя хоста)
+    pub fn set(&mut self, domain: &str, name: Option<&str>) -> Result<(), LimniError> {
+        let key = normalize_domain(domain)
+            .ok_or_else(|| LimniError::invalid_argument(format!("Invalid domain: {}", domain)))?;
+        match name {
+            Some(name) => {
+                self.overrides.insert(key, validate_name(name)?);
+            }
+            None => {
+                self.overrides.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    fn lookup(&self, domain: &str) -> Option<&str> {
+        self.overrides.get(domain).or_else(|| BUILTIN.get(domain)).map(String::as_str)
+    }
+
+    /// Название сайта по хосту. Проверяется сам хост и его родительские домены,
+    /// но не выше регистрируемого домена: `mail.google.com` -> `google.com`,
+    /// а `google.com.evil` к `google.com` отношения не имеет
+    pub fn name_for_host(&self, host: &str) -> Option<String> {
+        let host = normalize_domain(host).unwrap_or_else(|| host.to_ascii_lowercase());
+        let Some(registrable) = registrable_domain(&host) else {
+            return self.lookup(&host).map(str::to_string);
+        };
+
+        let mut domain = host.as_str();
+        loop {
+            if let Some(name) = self.lookup(domain) {
+                return Some(name.to_string());
+            }
+            if domain.len() <= registrable.len() {
+                return None;
+            }
+            domain = domain.split_once('.')?.1;
+        }
+    }
+}
+
+/// Состояние реестра названий, разделяемое между командами и вкладками
+#[derive(Default)]
+pub struct SiteNamesState {
+    pub store: Mutex<SiteNameStore>,
+}
+
+impl SiteNamesState {
+    /// Загружает пользовательские названия из директории данных профиля
+    pub fn load(app: &AppHandle) -> Self {
+        let store = match data_dir(app) {
+            Ok(dir) => SiteNameStore::load(dir.join(SITE_NAMES_FILE)),
+            Err(e) => {
+                println!("🦀 Rust: Site names will not be persisted: {}", e);
+                SiteNameStore::default()
+            }
+        };
+
+        Self { store: Mutex::new(store) }
+    }
+}
+
+/// Название сайта для хоста, если оно известно
+pub fn site_name(app: &AppHandle, host: &str) -> Option<String> {
+    match app.try_state::<SiteNamesState>() {
+        Some(state) => state.store.lock().unwrap().name_for_host(host),
+        None => SiteNameStore::default().name_for_host(host),
+    }
+}
+
+#[cfg(test)]
+mod tests;
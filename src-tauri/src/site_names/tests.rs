@@ -0,0 +1,38 @@
+use super::*;
+
+#[test]
+fn test_builtin_names_match_registrable_domain() {
+    let store = SiteNameStore::default();
+    assert_eq!(store.name_for_host("www.google.com").as_deref(), Some("Google"));
+    assert_eq!(store.name_for_host("accounts.google.com").as_deref(), Some("Google"));
+    assert_eq!(store.name_for_host("mail.google.com").as_deref(), Some("Gmail"));
+    assert_eq!(store.name_for_host("ru.wikipedia.org").as_deref(), Some("Wikipedia"));
+    assert_eq!(store.name_for_host("example.com"), None);
+}
+
+#[test]
+fn test_lookalike_hosts_are_not_matched() {
+    let store = SiteNameStore::default();
+    assert_eq!(store.name_for_host("notgoogle.com.evil"), None);
+    assert_eq!(store.name_for_host("google.com.evil"), None);
+    assert_eq!(store.name_for_host("notgoogle.com"), None);
+    assert_eq!(store.name_for_host("github.com.example.org"), None);
+}
+
+#[test]
+fn test_overrides_win_and_can_be_removed() {
+    let mut store = SiteNameStore::default();
+    store.set("WWW.GitHub.com", Some("  Code  ")).unwrap();
+    store.set("intranet", Some("Wiki")).unwrap();
+    store.set("яндекс.рф", Some("Яндекс")).unwrap();
+
+    assert_eq!(store.name_for_host("gist.github.com").as_deref(), Some("Code"));
+    assert_eq!(store.name_for_host("intranet").as_deref(), Some("Wiki"));
+    assert_eq!(store.name_for_host("www.xn--d1acpjx3f.xn--p1ai").as_deref(), Some("Яндекс"));
+    assert!(store.names().overrides.contains_key("github.com"));
+
+    store.set("github.com", None).unwrap();
+    assert_eq!(store.name_for_host("github.com").as_deref(), Some("GitHub"));
+    assert!(store.set("bad host", Some("X")).is_err());
+    assert!(store.set("example.com", Some("   ")).is_err());
+}
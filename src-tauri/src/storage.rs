@@ -0,0 +1,100 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use serde::{de::DeserializeOwned, Serialize};
+use tauri::{AppHandle, Manager};
+use crate::error::LimniError;
+use crate::profile::ProfileState;
+
+/// Корневая директория данных приложения (общая для всех профилей)
+pub fn app_data_root(app: &AppHandle) -> Result<PathBuf, LimniError> {
+    app.path().app_data_dir()
+        .map_err(|e| LimniError::io(format!("Failed to resolve app data dir: {}", e)))
+}
+
+/// Возвращает директорию данных текущего профиля, создавая её при необходимости
+pub fn data_dir(app: &AppHandle) -> Result<PathBuf, LimniError> {
+    let dir = match app.try_state::<ProfileState>().and_then(|profile| profile.dir.clone()) {
+        Some(dir) => dir,
+        None => app_data_root(app)?,
+    };
+    fs::create_dir_all(&dir)
+        .map_err(|e| LimniError::io(format!("Failed to create app data dir: {}", e)))?;
+    Ok(dir)
+}
+
+/// Читает JSON-файл. Если файла ещё нет, возвращает значение по умолчанию
+pub fn read_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, LimniError> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let data = fs::read(path)
+        .map_err(|e| LimniError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+    serde_json::from_slice(&data)
+        .map_err(|e| LimniError::io(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+/// Атомарно записывает JSON: сначала во временный файл рядом, затем rename поверх старого.
+/// Так при падении процесса на диске остаётся либо старая, либо новая версия целиком
+pub fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), LimniError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| LimniError::io(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+
+    let data = serde_json::to_vec_pretty(value)
+        .map_err(|e| LimniError::io(format!("Failed to serialize {}: {}", path.display(), e)))?;
+
+    let tmp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp_path)
+        .map_err(|e| LimniError::io(format!("Failed to create {}: {}", tmp_path.display(), e)))?;
+    file.write_all(&data)
+        .map_err(|e| LimniError::io(format!("Failed to write {}: {}", tmp_path.display(), e)))?;
+    file.sync_all()
+        .map_err(|e| LimniError::io(format!("Failed to sync {}: {}", tmp_path.display(), e)))?;
+    drop(file);
+
+    fs::rename(&tmp_path, path)
+        .map_err(|e| LimniError::io(format!("Failed to replace {}: {}", path.display(), e)))
+}
+
+/// Путь к резервной копии файла (предыдущая удачно записанная версия)
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("bak")
+}
+
+/// Как `write_json_atomic`, но перед записью сохраняет текущую версию файла в резервную копию
+pub fn write_json_with_backup<T: Serialize>(path: &Path, value: &T) -> Result<(), LimniError> {
+    if path.exists() {
+        fs::copy(path, backup_path(path))
+            .map_err(|e| LimniError::io(format!("Failed to back up {}: {}", path.display(), e)))?;
+    }
+    write_json_atomic(path, value)
+}
+
+/// Переименовывает нечитаемый файл в `*.corrupt`, чтобы следующая запись его не затёрла
+fn quarantine(path: &Path) {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".corrupt");
+    let target = path.with_file_name(name);
+    match fs::rename(path, &target) {
+        Ok(()) => println!("🦀 Rust: Unreadable {} kept as {}", path.display(), target.display()),
+        Err(e) => println!("🦀 Rust: Failed to move aside {}: {}", path.display(), e),
+    }
+}
+
+/// Читает JSON-файл, а если он повреждён — его резервную копию.
+/// Повреждённые файлы откладываются в `*.corrupt`: хранилище, начатое с нуля,
+/// не должно молча перезаписать данные пользователя
+pub fn read_json_with_backup<T: DeserializeOwned + Default>(path: &Path) -> Result<T, LimniError> {
+    read_json(path).or_else(|e| {
+        println!("🦀 Rust: {}, falling back to backup", e);
+        quarantine(path);
+        let backup = backup_path(path);
+        read_json(&backup).inspect_err(|_| quarantine(&backup))
+    })
+}
+
+#[cfg(test)]
+mod tests;
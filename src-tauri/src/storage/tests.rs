@@ -0,0 +1,39 @@
+use super::*;
+use crate::utils::now_millis;
+
+fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("limni_storage_test_{}_{}", name, now_millis()))
+}
+
+#[test]
+fn test_corrupt_file_falls_back_to_backup_and_is_kept() {
+    let dir = temp_dir("backup");
+    let path = dir.join("data.json");
+    write_json_with_backup(&path, &vec![1]).unwrap();
+    write_json_with_backup(&path, &vec![1, 2]).unwrap();
+    fs::write(&path, b"{ truncated").unwrap();
+
+    let restored: Vec<u32> = read_json_with_backup(&path).unwrap();
+    assert_eq!(restored, vec![1]);
+    assert!(!path.exists());
+    assert_eq!(fs::read(dir.join("data.json.corrupt")).unwrap(), b"{ truncated");
+
+    // Следующая запись не трогает ни резервную копию, ни отложенный файл
+    write_json_with_backup(&path, &vec![3]).unwrap();
+    assert_eq!(read_json::<Vec<u32>>(&backup_path(&path)).unwrap(), vec![1]);
+    assert!(dir.join("data.json.corrupt").exists());
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_unreadable_file_without_backup_is_moved_aside() {
+    let dir = temp_dir("no_backup");
+    let path = dir.join("data.json");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&path, b"not json").unwrap();
+
+    assert_eq!(read_json_with_backup::<Vec<u32>>(&path).unwrap(), Vec::<u32>::new());
+    write_json_with_backup(&path, &vec![1]).unwrap();
+    assert_eq!(fs::read(dir.join("data.json.corrupt")).unwrap(), b"not json");
+    let _ = fs::remove_dir_all(dir);
+}
@@ -1,114 +1,462 @@
-use reqwest::Client;
-use base64::{engine::general_purpose, Engine as _};
-
-/// Команда для получения фавиконки через бэкенд
-pub async fn fetch_favicon_backend(url: String) -> Result<String, String> {
-    let client = Client::new();
-    
-    // Пытаемся получить фавиконку по указанному URL
-    // Favicon URL обычно это host + /favicon.ico
-    let _parsed_url = url::Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
-    let _base_url = format!("{}://{}/", _parsed_url.scheme(), _parsed_url.host_str().unwrap_or(""));
-    let favicon_url_candidate = _parsed_url.join("/favicon.ico").map_err(|e| format!("Failed to build favicon URL: {}", e))?;
-
-    // Попробуем сначала favicon.ico
-    let response = client.get(favicon_url_candidate.as_str()).send().await;
-
-    let final_response = match response {
-        Ok(res) if res.status().is_success() => {
-            // Если получили 200 OK, используем этот ответ
-            Some(res)
-        },
-        _ => {
-            // Если favicon.ico не найден или ошибка, попробуем получить через DuckDuckGo Favicon API
-            let duckduckgo_favicon_api_url = format!("https://icons.duckduckgo.com/ip3/{}.ico", _parsed_url.host_str().unwrap_or(""));
-            let ddg_response = client.get(&duckduckgo_favicon_api_url).send().await;
-            
-            match ddg_response {
-                Ok(res) if res.status().is_success() => Some(res),
-                _ => None, // Не удалось получить фавиконку ни одним способом
-            }
-        }
+use std::sync::LazyLock;
+use std::time::SystemTime;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use url::Url;
+use crate::error::LimniError;
+use crate::eval::{eval_in_tab, DEFAULT_EVAL_TIMEOUT};
+use crate::i18n::t;
+use crate::net::network;
+use crate::registry::normalize_title;
+use crate::site_names::site_name;
+
+/// Текущее время в миллисекундах с начала эпохи Unix
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Сколько читаем от HTML страницы в поисках конца `<head>`
+pub const MAX_HEAD_BYTES: usize = 256 * 1024;
+
+/// Сколько байт от начала документа просматриваем в поисках `<meta charset>`
+const CHARSET_SCAN_BYTES: usize = 4096;
+
+static TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<([a-z][a-z0-9:-]*)((?:[^>"']|"[^"]*"|'[^']*')*)>"#).unwrap()
+});
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([^\s"'<>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#).unwrap()
+});
+static TITLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<title\b(?:[^>"']|"[^"]*"|'[^']*')*>(.*?)</title\s*>"#).unwrap()
+});
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[a-zA-Z][a-zA-Z0-9]{1,31});").unwrap());
+static CHARSET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)charset\s*=\s*["']?\s*([a-z0-9_.:-]+)"#).unwrap());
+
+/// Тег HTML: имя и атрибуты в нижнем регистре, значения с раскрытыми сущностями
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlTag {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl HtmlTag {
+    /// Непустое значение атрибута без пробелов по краям
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Открывающие теги с указанными именами в порядке появления в документе
+pub fn html_tags(html: &str, names: &[&str]) -> Vec<HtmlTag> {
+    TAG.captures_iter(html)
+        .filter(|caps| names.iter().any(|name| caps[1].eq_ignore_ascii_case(name)))
+        .map(|caps| HtmlTag {
+            name: caps[1].to_ascii_lowercase(),
+            attributes: ATTRIBUTE.captures_iter(&caps[2])
+                .map(|attr| {
+                    let value = attr.get(2).or(attr.get(3)).or(attr.get(4)).map_or("", |m| m.as_str());
+                    (attr[1].to_ascii_lowercase(), decode_entities(value))
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Именованные сущности, которые встречаются в заголовках и описаниях страниц
+fn named_entity(name: &str) -> Option<char> {
+    let c = match name {
+        "amp" => '&', "lt" => '<', "gt" => '>', "quot" => '"', "apos" => '\'',
+        "nbsp" => '\u{a0}', "shy" => '\u{ad}', "copy" => '©', "reg" => '®', "trade" => '™',
+        "hellip" => '…', "mdash" => '—', "ndash" => '–', "minus" => '−', "bull" => '•', "middot" => '·',
+        "laquo" => '«', "raquo" => '»', "lsaquo" => '‹', "rsaquo" => '›',
+        "lsquo" => '‘', "rsquo" => '’', "sbquo" => '‚', "ldquo" => '“', "rdquo" => '”', "bdquo" => '„',
+        "euro" => '€', "pound" => '£', "yen" => '¥', "cent" => '¢', "curren" => '¤',
+        "sect" => '§', "para" => '¶', "deg" => '°', "plusmn" => '±', "times" => '×', "divide" => '÷',
+        "frac12" => '½', "frac14" => '¼', "frac34" => '¾', "sup2" => '²', "sup3" => '³', "micro" => 'µ',
+        "iexcl" => '¡', "iquest" => '¿', "dagger" => '†', "Dagger" => '‡', "permil" => '‰',
+        "larr" => '←', "rarr" => '→', "uarr" => '↑', "darr" => '↓', "harr" => '↔',
+        "hearts" => '♥', "star" => '☆', "check" => '✓', "thinsp" => '\u{2009}', "ensp" => '\u{2002}',
+        "emsp" => '\u{2003}', "zwnj" => '\u{200c}', "zwj" => '\u{200d}', "lrm" => '\u{200e}', "rlm" => '\u{200f}',
+        "Agrave" => 'À', "Aacute" => 'Á', "Acirc" => 'Â', "Atilde" => 'Ã', "Auml" => 'Ä', "Aring" => 'Å',
+        "AElig" => 'Æ', "Ccedil" => 'Ç', "Egrave" => 'È', "Eacute" => 'É', "Ecirc" => 'Ê', "Euml" => 'Ë',
+        "Igrave" => 'Ì', "Iacute" => 'Í', "Icirc" => 'Î', "Iuml" => 'Ï', "Ntilde" => 'Ñ', "Ograve" => 'Ò',
+        "Oacute" => 'Ó', "Ocirc" => 'Ô', "Otilde" => 'Õ', "Ouml" => 'Ö', "Oslash" => 'Ø', "Ugrave" => 'Ù',
+        "Uacute" => 'Ú', "Ucirc" => 'Û', "Uuml" => 'Ü', "Yacute" => 'Ý', "szlig" => 'ß',
+        "agrave" => 'à', "aacute" => 'á', "acirc" => 'â', "atilde" => 'ã', "auml" => 'ä', "aring" => 'å',
+        "aelig" => 'æ', "ccedil" => 'ç', "egrave" => 'è', "eacute" => 'é', "ecirc" => 'ê', "euml" => 'ë',
+        "igrave" => 'ì', "iacute" => 'í', "icirc" => 'î', "iuml" => 'ï', "ntilde" => 'ñ', "ograve" => 'ò',
+        "oacute" => 'ó', "ocirc" => 'ô', "otilde" => 'õ', "ouml" => 'ö', "oslash" => 'ø', "ugrave" => 'ù',
+        "uacute" => 'ú', "ucirc" => 'û', "uuml" => 'ü', "yacute" => 'ý', "yuml" => 'ÿ',
+        _ => return None,
     };
+    Some(c)
+}
 
-    if let Some(res) = final_response {
-        let content_type = res.headers()
-            .get(reqwest::header::CONTENT_TYPE)
-            .and_then(|value| value.to_str().ok())
-            .unwrap_or("image/x-icon")
-            .split(';')
-            .next()
-            .unwrap_or("image/x-icon")
-            .to_string();
-
-        let bytes = res.bytes().await.map_err(|e| format!("Failed to read favicon bytes: {}", e))?;
-        let encoded_favicon = general_purpose::STANDARD.encode(&bytes);
-        Ok(format!("data:{};base64,{}", content_type, encoded_favicon))
-    } else {
-        Err("Failed to fetch favicon.".to_string())
+/// Символ по числовой ссылке. Коды 0x80–0x9F браузеры читают как windows-1252,
+/// недопустимые коды заменяются на U+FFFD
+fn numeric_entity(code: u32) -> char {
+    if (0x80..=0x9f).contains(&code) {
+        let byte = [code as u8];
+        let (decoded, _) = WINDOWS_1252.decode_without_bom_handling(&byte);
+        return decoded.chars().next().unwrap_or('\u{fffd}');
     }
+    char::from_u32(code).filter(|c| *c != '\0').unwrap_or('\u{fffd}')
 }
 
-/// Utility: Fetch real <title> of a page for more accurate tab titles
-pub async fn fetch_page_title_backend(url: String) -> Result<String, String> {
-    // Only handle http/https URLs for now
-    if !url.starts_with("http") {
-        return Err("Unsupported URL scheme".to_string());
+/// Раскрывает именованные и числовые ссылки на символы (`&amp;`, `&#8212;`, `&#x2014;`).
+/// Неизвестные именованные ссылки остаются как есть
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
     }
+    ENTITY.replace_all(text, |caps: &regex::Captures| {
+        let entity = &caps[1];
+        let decoded = match entity.strip_prefix('#') {
+            Some(number) => {
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16),
+                    None => number.parse(),
+                };
+                Some(code.map_or('\u{fffd}', numeric_entity))
+            }
+            None => named_entity(entity),
+        };
+        decoded.map_or_else(|| caps[0].to_string(), String::from)
+    }).into_owned()
+}
 
-    let client = Client::new();
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let body = response
-        .text()
-        .await
-        .map_err(|e| format!("Body read error: {}", e))?;
-
-    // Extract <title> tag contents using a simple regex
-    use regex::Regex;
-    let re = Regex::new(r"(?is)<title[^>]*>(.*?)</title>")
-        .map_err(|e| format!("Regex error: {}", e))?;
-
-    if let Some(caps) = re.captures(&body) {
-        let title = caps.get(1).unwrap().as_str().trim();
-        if !title.is_empty() {
-            return Ok(title.to_string());
+/// Кодировка из значения вида `text/html; charset=...`
+fn charset_from(value: &str) -> Option<&'static Encoding> {
+    CHARSET.captures(value).and_then(|caps| Encoding::for_label(caps[1].as_bytes()))
+}
+
+/// Кодировка, объявленная в `<meta charset>` или `<meta http-equiv="Content-Type">`.
+/// UTF-16 в meta по стандарту HTML означает UTF-8: до meta документ уже читается как ASCII
+fn meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let prefix = String::from_utf8_lossy(&bytes[..bytes.len().min(CHARSET_SCAN_BYTES)]);
+    let encoding = html_tags(&prefix, &["meta"]).iter().find_map(|meta| {
+        if let Some(charset) = meta.attr("charset") {
+            return Encoding::for_label(charset.as_bytes());
         }
+        let is_content_type = meta.attr("http-equiv").is_some_and(|v| v.eq_ignore_ascii_case("content-type"));
+        meta.attr("content").filter(|_| is_content_type).and_then(charset_from)
+    })?;
+    Some(if encoding == UTF_16LE || encoding == UTF_16BE { UTF_8 } else { encoding })
+}
+
+/// Декодирует начало HTML документа: BOM, затем charset из Content-Type, затем
+/// `<meta charset>`. Без объявления — UTF-8, если байты на него похожи, иначе windows-1252
+pub fn decode_html(bytes: &[u8], content_type: Option<&str>) -> String {
+    let (encoding, bom_length) = Encoding::for_bom(bytes).unwrap_or_else(|| {
+        let declared = content_type.and_then(charset_from).or_else(|| meta_charset(bytes));
+        // Обрезанный на середине символа конец UTF-8 не считается ошибкой
+        let looks_utf8 = match std::str::from_utf8(bytes) {
+            Ok(_) => true,
+            Err(e) => e.error_len().is_none(),
+        };
+        (declared.unwrap_or(if looks_utf8 { UTF_8 } else { WINDOWS_1252 }), 0)
+    });
+    encoding.decode_without_bom_handling(&bytes[bom_length..]).0.into_owned()
+}
+
+fn contains_ignore_case(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window.eq_ignore_ascii_case(needle))
+}
+
+/// Прочитан ли `<head>` целиком: дальше страницу для метаданных не читаем
+pub fn head_complete(html: &[u8]) -> bool {
+    contains_ignore_case(html, b"</head") || contains_ignore_case(html, b"<body")
+}
+
+/// Часть документа до конца `<head>`
+pub fn head_section(html: &str) -> &str {
+    let lower = html.to_ascii_lowercase();
+    let end = [lower.find("</head"), lower.find("<body")].into_iter().flatten().min();
+    &html[..end.unwrap_or(html.len())]
+}
+
+/// Начало HTML страницы
+#[derive(Debug, Clone)]
+pub struct HtmlHead {
+    /// Адрес страницы после редиректов
+    pub url: Url,
+    /// Документ до конца `<head>`
+    pub html: String,
+}
+
+/// Загружает страницу только до конца `<head>` и декодирует её в объявленной
+/// кодировке. `None` — по адресу не HTML документ
+pub async fn fetch_html_head(app: &AppHandle, url: &Url) -> Result<Option<HtmlHead>, LimniError> {
+    let network = network(app);
+    let mut response = network.send(network.get(url.as_str())).await?;
+    let content_type = response.headers().get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    if content_type.as_deref().is_some_and(|value| !value.to_ascii_lowercase().contains("html")) {
+        return Ok(None);
     }
 
-    // Fallback: return the domain name if title not found
-    if let Ok(parsed_url) = url::Url::parse(&url) {
-        return Ok(parsed_url
-            .host_str()
-            .unwrap_or("Новая вкладка")
-            .to_string());
+    let url = response.url().clone();
+    let bytes = response.read_until(MAX_HEAD_BYTES, head_complete).await?;
+    let html = decode_html(&bytes, content_type.as_deref());
+    Ok(Some(HtmlHead { url, html: head_section(&html).to_string() }))
+}
+
+/// Текст из разметки: сущности раскрыты, пробелы схлопнуты
+pub fn clean_text(text: &str) -> Option<String> {
+    Some(normalize_title(&decode_entities(text))).filter(|text| !text.is_empty())
+}
+
+/// Значение `<meta name|property="key" content="...">`
+pub fn meta_content(metas: &[HtmlTag], key: &str) -> Option<String> {
+    metas.iter()
+        .filter(|meta| {
+            meta.attr("property").or(meta.attr("name"))
+                .is_some_and(|name| name.eq_ignore_ascii_case(key))
+        })
+        .find_map(|meta| meta.attr("content").and_then(clean_text))
+}
+
+/// Заголовки, которые объявляет `<head>` страницы
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeadTitles {
+    pub title: Option<String>,
+    pub og_title: Option<String>,
+    pub twitter_title: Option<String>,
+}
+
+impl HeadTitles {
+    /// `<title>`, затем og:title, затем twitter:title
+    pub fn best(self) -> Option<String> {
+        self.title.or(self.og_title).or(self.twitter_title)
     }
+}
 
-    Err("Unable to determine title".to_string())
-}
-
-/// Определяет title на основе URL (как fallback)
-pub fn get_title_from_url(url_str: &str) -> String {
-    if url_str.contains("yandex.ru") {
-        "Яндекс".to_string()
-    } else if url_str.contains("google.com") {
-        "Google".to_string()
-    } else if url_str.contains("youtube.com") {
-        "YouTube".to_string()
-    } else if url_str.contains("github.com") {
-        "GitHub".to_string()
-    } else if url_str == "about:blank" {
-        "Новая вкладка".to_string()
-    } else {
-        // Пытаемся извлечь домен из URL
-        if let Ok(parsed_url) = url::Url::parse(url_str) {
-            parsed_url.host_str().unwrap_or("Новая вкладка").to_string()
-        } else {
-            "Новая вкладка".to_string()
+/// Находит заголовки в `<head>` документа. Атрибуты значений уже раскрыты
+/// `html_tags`, а текст `<title>` раскрывается здесь
+pub fn parse_head_titles(html: &str) -> HeadTitles {
+    let head = head_section(html);
+    let metas = html_tags(head, &["meta"]);
+    HeadTitles {
+        title: TITLE.captures(head).and_then(|caps| clean_text(&caps[1])),
+        og_title: meta_content(&metas, "og:title"),
+        twitter_title: meta_content(&metas, "twitter:title"),
+    }
+}
+
+/// Базовый адрес документа для относительных ссылок: `<base href>` или адрес страницы
+pub fn document_base(html: &str, page_url: &Url) -> Url {
+    html_tags(html, &["base"]).iter()
+        .find_map(|base| base.attr("href"))
+        .and_then(|href| page_url.join(href).ok())
+        .unwrap_or_else(|| page_url.clone())
+}
+
+/// Данные Open Graph (`og:*`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenGraph {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub url: Option<String>,
+    pub site_name: Option<String>,
+    /// `og:type`
+    pub kind: Option<String>,
+}
+
+/// Данные карточки Twitter (`twitter:*`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwitterCard {
+    pub card: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub site: Option<String>,
+    pub creator: Option<String>,
+}
+
+/// Лента новостей сайта
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedLink {
+    pub url: String,
+    pub title: Option<String>,
+    /// "rss", "atom" или "json"
+    pub kind: String,
+}
+
+/// Метаданные страницы из её `<head>`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageMetadata {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub canonical_url: Option<String>,
+    pub open_graph: OpenGraph,
+    pub twitter: TwitterCard,
+    /// CSS цвет из `<meta name="theme-color">`
+    pub theme_color: Option<String>,
+    pub language: Option<String>,
+    pub feeds: Vec<FeedLink>,
+    pub manifest_url: Option<String>,
+}
+
+/// Вид ленты по MIME-типу из `<link rel="alternate" type="...">`
+fn feed_kind(mime: &str) -> Option<&'static str> {
+    match mime.to_ascii_lowercase().as_str() {
+        "application/rss+xml" => Some("rss"),
+        "application/atom+xml" => Some("atom"),
+        "application/feed+json" | "application/json+feed" => Some("json"),
+        _ => None,
+    }
+}
+
+fn has_rel(link: &HtmlTag, rel: &str) -> bool {
+    link.attr("rel").is_some_and(|value| value.split_whitespace().any(|token| token.eq_ignore_ascii_case(rel)))
+}
+
+/// Разбирает метаданные из `<head>` документа. Относительные адреса
+/// разрешаются от `<base href>` или `page_url`
+pub fn parse_page_metadata(html: &str, page_url: &Url) -> PageMetadata {
+    let head = head_section(html);
+    let base = document_base(head, page_url);
+    let tags = html_tags(head, &["html", "meta", "link"]);
+    let metas: Vec<HtmlTag> = tags.iter().filter(|tag| tag.name == "meta").cloned().collect();
+    let links: Vec<&HtmlTag> = tags.iter().filter(|tag| tag.name == "link").collect();
+
+    let meta = |key: &str| meta_content(&metas, key);
+    let meta_url = |key: &str| meta(key).and_then(|href| base.join(&href).ok()).map(String::from);
+    let link_url = |rel: &str| {
+        links.iter()
+            .filter(|link| has_rel(link, rel))
+            .find_map(|link| link.attr("href"))
+            .and_then(|href| base.join(href).ok())
+            .map(String::from)
+    };
+
+    let open_graph = OpenGraph {
+        title: meta("og:title"),
+        description: meta("og:description"),
+        image: meta_url("og:image").or_else(|| meta_url("og:image:url")).or_else(|| meta_url("og:image:secure_url")),
+        url: meta_url("og:url"),
+        site_name: meta("og:site_name"),
+        kind: meta("og:type"),
+    };
+    let twitter = TwitterCard {
+        card: meta("twitter:card"),
+        title: meta("twitter:title"),
+        description: meta("twitter:description"),
+        image: meta_url("twitter:image").or_else(|| meta_url("twitter:image:src")),
+        site: meta("twitter:site"),
+        creator: meta("twitter:creator"),
+    };
+
+    // Цвет без media подходит для любой темы, поэтому он важнее остальных
+    let theme_colors: Vec<&HtmlTag> = metas.iter()
+        .filter(|tag| tag.attr("name").is_some_and(|name| name.eq_ignore_ascii_case("theme-color")))
+        .collect();
+    let theme_color = theme_colors.iter().find(|tag| tag.attr("media").is_none())
+        .or(theme_colors.first())
+        .and_then(|tag| tag.attr("content"))
+        .map(str::to_string);
+
+    let language = tags.iter()
+        .filter(|tag| tag.name == "html")
+        .find_map(|tag| tag.attr("lang").or(tag.attr("xml:lang")))
+        .map(str::to_string)
+        .or_else(|| {
+            metas.iter()
+                .filter(|tag| tag.attr("http-equiv").is_some_and(|v| v.eq_ignore_ascii_case("content-language")))
+                .find_map(|tag| tag.attr("content").and_then(clean_text))
+        });
+
+    let mut feeds: Vec<FeedLink> = Vec::new();
+    for link in links.iter().filter(|link| has_rel(link, "alternate")) {
+        let (Some(kind), Some(href)) = (link.attr("type").and_then(feed_kind), link.attr("href")) else {
+            continue;
+        };
+        let Ok(url) = base.join(href) else {
+            continue;
+        };
+        if !feeds.iter().any(|feed| feed.url == url.as_str()) {
+            feeds.push(FeedLink { url: url.to_string(), title: link.attr("title").and_then(clean_text), kind: kind.to_string() });
         }
     }
-} 
\ No newline at end of file
+
+    PageMetadata {
+        url: page_url.to_string(),
+        title: parse_head_titles(head).best(),
+        description: meta("description").or_else(|| open_graph.description.clone()).or_else(|| twitter.description.clone()),
+        canonical_url: link_url("canonical"),
+        open_graph,
+        twitter,
+        theme_color,
+        language,
+        feeds,
+        manifest_url: link_url("manifest"),
+    }
+}
+
+/// Выражение, которое возвращает адрес документа и его `<head>` вместе
+/// с открывающим тегом `<html>` (ради атрибута lang)
+const DOCUMENT_HEAD_EXPRESSION: &str = r#"(function () {
+    var root = document.documentElement;
+    var html = root ? root.cloneNode(false).outerHTML.replace(/<\/html>\s*$/i, '') : '';
+    var head = document.head ? document.head.outerHTML : '';
+    return { url: location.href, html: (html + head).slice(0, 262144) };
+})()"#;
+
+#[derive(Deserialize)]
+struct DocumentHead {
+    url: String,
+    html: String,
+}
+
+/// Метаданные страницы, открытой во вкладке. Читается текущий документ
+/// вкладки, поэтому учитываются и теги, добавленные скриптами страницы
+pub async fn get_page_metadata(app: &AppHandle, tab_id: &str) -> Result<PageMetadata, LimniError> {
+    let value = eval_in_tab(app, tab_id, DOCUMENT_HEAD_EXPRESSION, DEFAULT_EVAL_TIMEOUT).await?;
+    let document: DocumentHead = serde_json::from_value(value)
+        .map_err(|e| LimniError::internal(format!("Invalid document head: {}", e)))?;
+    let page_url = Url::parse(&document.url).map_err(|e| LimniError::invalid_url(&document.url, e))?;
+    Ok(parse_page_metadata(&document.html, &page_url))
+}
+
+/// Заголовок страницы по сети, без webview: читается только `<head>`.
+/// Если страница его не объявляет — имя хоста
+pub async fn fetch_page_title_backend(app: &AppHandle, url: String) -> Result<String, LimniError> {
+    let page_url = Url::parse(&url).map_err(|e| LimniError::invalid_url(&url, e))?;
+    if !matches!(page_url.scheme(), "http" | "https") {
+        return Err(LimniError::unsupported_scheme(url));
+    }
+
+    let title = fetch_html_head(app, &page_url).await?
+        .and_then(|head| parse_head_titles(&head.html).best());
+    Ok(title.unwrap_or_else(|| page_url.host_str().map(str::to_string).unwrap_or_else(|| t(app, "tab.new"))))
+}
+
+/// Title вкладки по URL, пока страница не сообщила свой: название сайта
+/// из реестра или имя хоста
+pub fn get_title_from_url(app: &AppHandle, url_str: &str) -> String {
+    let host = Url::parse(url_str).ok()
+        .and_then(|parsed_url| parsed_url.host_str().map(str::to_string));
+    match host {
+        Some(host) => site_name(app, &host).unwrap_or(host),
+        None => t(app, "tab.new"),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,144 @@
+use super::*;
+
+#[test]
+fn test_decode_entities() {
+    assert_eq!(decode_entities("Tom &amp; Jerry"), "Tom & Jerry");
+    assert_eq!(decode_entities("&laquo;Новости&raquo; &#8212; &#x41;&#X42;"), "«Новости» — AB");
+    assert_eq!(decode_entities("caf&eacute;&nbsp;bar"), "café\u{a0}bar");
+    assert_eq!(decode_entities("&#150; &#0; &#xD800;"), "– \u{fffd} \u{fffd}");
+    assert_eq!(decode_entities("&unknown; AT&T &amp"), "&unknown; AT&T &amp");
+}
+
+#[test]
+fn test_html_tags_reads_attributes_in_any_quoting() {
+    let tags = html_tags(
+        r#"<META name=description content="a > b &amp; c"><link rel='icon' href=/a.png><p class="x"><meta charset=utf-8>"#,
+        &["meta", "link"],
+    );
+    assert_eq!(tags.len(), 3);
+    assert_eq!(tags[0].name, "meta");
+    assert_eq!(tags[0].attr("content"), Some("a > b & c"));
+    assert_eq!(tags[1].attr("href"), Some("/a.png"));
+    assert_eq!(tags[2].attr("charset"), Some("utf-8"));
+    assert_eq!(tags[2].attr("content"), None);
+}
+
+#[test]
+fn test_decode_html_honours_declared_charset() {
+    // "Привет" в windows-1251
+    let cp1251 = b"<title>\xcf\xf0\xe8\xe2\xe5\xf2</title>";
+    assert_eq!(decode_html(cp1251, Some("text/html; charset=windows-1251")), "<title>Привет</title>");
+
+    let mut with_meta = br#"<meta http-equiv="Content-Type" content="text/html; charset=windows-1251">"#.to_vec();
+    with_meta.extend_from_slice(cp1251);
+    assert!(decode_html(&with_meta, Some("text/html")).ends_with("<title>Привет</title>"));
+
+    let koi8 = b"<meta charset=\"koi8-r\"><title>\xf0\xd2\xc9\xd7\xc5\xd4</title>";
+    assert!(decode_html(koi8, None).ends_with("<title>Привет</title>"));
+}
+
+#[test]
+fn test_decode_html_defaults() {
+    // BOM важнее заголовка
+    assert_eq!(decode_html("\u{feff}Привет".as_bytes(), Some("text/html; charset=iso-8859-1")), "Привет");
+    // UTF-8, обрезанный на середине символа
+    let utf8 = "Привет".as_bytes();
+    assert_eq!(decode_html(&utf8[..utf8.len() - 1], None), "Приве\u{fffd}");
+    // Байты, которые не могут быть UTF-8, читаются как windows-1252
+    assert_eq!(decode_html(b"caf\xe9 au lait", None), "café au lait");
+    // UTF-16 в meta означает UTF-8
+    assert_eq!(decode_html("<meta charset=utf-16>é".as_bytes(), None), "<meta charset=utf-16>é");
+}
+
+#[test]
+fn test_head_section_and_completion() {
+    assert_eq!(head_section("<head><title>A</title></HEAD><body><title>B</title>"), "<head><title>A</title>");
+    assert_eq!(head_section("<title>A</title>"), "<title>A</title>");
+    assert!(head_complete(b"<html><HEAD></HEAD>"));
+    assert!(head_complete(b"<html><Body>"));
+    assert!(!head_complete(b"<html><head><link rel=icon"));
+}
+
+#[test]
+fn test_parse_head_titles_cleans_text() {
+    let titles = parse_head_titles(
+        "<head><title data-x=\"1\">\n  Rust &amp;\tCargo  \n</title>\
+         <meta property=\"og:title\" content=\"OG &quot;title&quot;\">\
+         <meta name=\"twitter:title\" content=\"Tweet\"></head><body><svg><title>icon</title></svg>",
+    );
+    assert_eq!(titles.title.as_deref(), Some("Rust & Cargo"));
+    assert_eq!(titles.og_title.as_deref(), Some("OG \"title\""));
+    assert_eq!(titles.twitter_title.as_deref(), Some("Tweet"));
+    assert_eq!(titles.best().as_deref(), Some("Rust & Cargo"));
+}
+
+#[test]
+fn test_parse_head_titles_falls_back_to_social_titles() {
+    let og = parse_head_titles("<title>   </title><meta property=og:title content='Open Graph'><meta name=twitter:title content=Tw>");
+    assert_eq!(og.best().as_deref(), Some("Open Graph"));
+
+    let twitter = parse_head_titles("<head><meta property=\"twitter:title\" content=\"Tw\"></head>");
+    assert_eq!(twitter.best().as_deref(), Some("Tw"));
+
+    assert_eq!(parse_head_titles("<head></head><body><title>late</title>").best(), None);
+}
+
+#[test]
+fn test_parse_page_metadata() {
+    let html = r##"<html lang="pt-BR" class="no-js"><head>
+        <base href="https://example.com/blog/">
+        <title>Post &mdash; Blog</title>
+        <meta name="description" content="  A short   description ">
+        <link rel="canonical" href="/post">
+        <meta property="og:title" content="Post">
+        <meta property="og:image" content="img/cover.png">
+        <meta property="og:site_name" content="Blog">
+        <meta property="og:type" content="article">
+        <meta name="twitter:card" content="summary_large_image">
+        <meta name="twitter:site" content="@blog">
+        <meta name="theme-color" media="(prefers-color-scheme: dark)" content="#000000">
+        <meta name="theme-color" content="#336699">
+        <link rel="alternate" type="application/rss+xml" title="RSS" href="feed.xml">
+        <link rel="alternate" type="application/atom+xml" href="/atom.xml">
+        <link rel="alternate" hreflang="en" href="/en/">
+        <link rel="manifest" href="/manifest.json">
+    </head><body><link rel="canonical" href="/wrong"></body></html>"##;
+    let page_url = Url::parse("https://example.com/blog/post?utm=1").unwrap();
+    let metadata = parse_page_metadata(html, &page_url);
+
+    assert_eq!(metadata.url, "https://example.com/blog/post?utm=1");
+    assert_eq!(metadata.title.as_deref(), Some("Post — Blog"));
+    assert_eq!(metadata.description.as_deref(), Some("A short description"));
+    assert_eq!(metadata.canonical_url.as_deref(), Some("https://example.com/post"));
+    assert_eq!(metadata.open_graph.image.as_deref(), Some("https://example.com/blog/img/cover.png"));
+    assert_eq!(metadata.open_graph.site_name.as_deref(), Some("Blog"));
+    assert_eq!(metadata.open_graph.kind.as_deref(), Some("article"));
+    assert_eq!(metadata.twitter.card.as_deref(), Some("summary_large_image"));
+    assert_eq!(metadata.twitter.site.as_deref(), Some("@blog"));
+    assert_eq!(metadata.theme_color.as_deref(), Some("#336699"));
+    assert_eq!(metadata.language.as_deref(), Some("pt-BR"));
+    assert_eq!(metadata.manifest_url.as_deref(), Some("https://example.com/manifest.json"));
+    assert_eq!(metadata.feeds, vec![
+        FeedLink { url: "https://example.com/blog/feed.xml".to_string(), title: Some("RSS".to_string()), kind: "rss".to_string() },
+        FeedLink { url: "https://example.com/atom.xml".to_string(), title: None, kind: "atom".to_string() },
+    ]);
+}
+
+#[test]
+fn test_parse_page_metadata_fallbacks() {
+    let html = r#"<head>
+        <meta http-equiv="Content-Language" content="ru">
+        <meta property="og:description" content="From OG">
+        <meta name="twitter:image:src" content="https://cdn.example.com/card.jpg">
+        <meta name="theme-color" media="(prefers-color-scheme: dark)" content="black">
+    </head>"#;
+    let metadata = parse_page_metadata(html, &Url::parse("https://example.com/").unwrap());
+
+    assert_eq!(metadata.title, None);
+    assert_eq!(metadata.description.as_deref(), Some("From OG"));
+    assert_eq!(metadata.language.as_deref(), Some("ru"));
+    assert_eq!(metadata.twitter.image.as_deref(), Some("https://cdn.example.com/card.jpg"));
+    assert_eq!(metadata.theme_color.as_deref(), Some("black"));
+    assert!(metadata.feeds.is_empty());
+    assert_eq!(metadata.canonical_url, None);
+}
@@ -1,52 +1,90 @@
-use tauri::{Manager, LogicalPosition, LogicalSize, AppHandle, WebviewUrl, Position, Size, Emitter};
-use tauri::webview::WebviewBuilder;
-use crate::state::{WebviewState, HEADER_HEIGHT};
-use crate::utils::{fetch_favicon_backend, fetch_page_title_backend, get_title_from_url};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{Manager, AppHandle, WebviewUrl, Emitter};
+use tauri::webview::{DownloadEvent, PageLoadEvent, WebviewBuilder};
+use crate::registry::{registry, tab_webview, LoadState, TabRecord};
+use crate::error::LimniError;
+use crate::utils::{get_title_from_url, now_millis};
+use crate::favicon::fetch_favicon_backend;
+use crate::eval::{eval_in_tab, DEFAULT_EVAL_TIMEOUT};
+use crate::history;
+use crate::session;
+use crate::private;
+use crate::profile;
+use crate::containers;
+use crate::data_store;
+use crate::layout;
+use crate::page_load;
 
-/// Создает новый CHILD webview для вкладки (embedded, ниже UI)
+/// Метка webview для вкладки
+pub fn webview_label_for(tab_id: &str) -> String {
+    format!("tab-{}", tab_id)
+}
+
+/// Id вкладки по метке её webview
+pub fn tab_id_for_label(webview_label: &str) -> Option<&str> {
+    webview_label.strip_prefix("tab-")
+}
+
+/// Создает новый CHILD webview для вкладки (embedded, ниже UI) в указанном окне
 pub async fn create_tab_webview_impl(
     app: AppHandle,
+    window_label: String,
     tab_id: String,
     url: String,
-    _title: String,
-) -> Result<String, String> {
-    let webview_label = format!("tab-{}", tab_id);
+    title: String,
+    container_id: Option<String>,
+) -> Result<String, LimniError> {
+    let webview_label = webview_label_for(&tab_id);
     
-    // Получаем главное окно
-    let main_window = app.get_window("main")
-        .ok_or("Main window not found")?;
+    // В приватном окне контейнеры не используются: у него своё временное хранилище
+    let is_private = private::is_private_window(&window_label);
+    let container_id = container_id.filter(|_| !is_private);
     
-    // Получаем размеры окна
-    let window_size = main_window.inner_size()
-        .map_err(|e| format!("Failed to get window size: {}", e))?;
+    // Получаем окно, которому будет принадлежать вкладка
+    let main_window = app.get_window(&window_label)
+        .ok_or_else(|| LimniError::window_not_found(&window_label))?;
+    
+    // Область под header панелями с учётом масштаба экрана
+    let bounds = layout::window_content_bounds(&main_window)?;
     
     // Создаем webview URL
     let webview_url = if url.starts_with("http://") || url.starts_with("https://") {
-        WebviewUrl::External(url.parse().map_err(|e| format!("Invalid URL: {}", e))?)
+        WebviewUrl::External(url.parse().map_err(|e| LimniError::invalid_url(&url, e))?)
     } else {
         WebviewUrl::App("index.html".into())
     };
     
     // КЛЮЧЕВОЕ ОТЛИЧИЕ: создаем CHILD WebView, который будет ниже UI
-    let webview_builder = WebviewBuilder::new(webview_label.clone(), webview_url)
+    let mut webview_builder = WebviewBuilder::new(webview_label.clone(), webview_url)
         .auto_resize() // Автоматически изменяет размер при изменении окна
         .transparent(false) // Убираем прозрачность
         .focused(false) // Child WebView не должен получать фокус автоматически
         .on_navigation({
             let app_handle = app.clone();
             let tab_id_clone = tab_id.clone();
+            let container_id = container_id.clone();
             move |url| {
                 let url_string = url.to_string();
                 
+                // Новая навигация: до ответа страницы показываем title из URL
+                let url_based_title = get_title_from_url(&app_handle, &url_string);
+                let new_url = url_string.clone();
+                let title_for_registry = url_based_title.clone();
+                registry(&app_handle).update(&tab_id_clone, move |tab| tab.begin_navigation(&new_url, &title_for_registry));
+                
+                // Записываем посещение в историю и снимок сессии (кроме приватных вкладок)
+                if !is_private {
+                    crate::history::record_visit(&app_handle, &url_string, &tab_id_clone);
+                }
+                session::update(&app_handle, |s| s.set_url(&tab_id_clone, &url_string));
+                
                 // Отправляем событие об изменении URL во frontend
                 let _ = app_handle.emit("webview-url-changed", serde_json::json!({
                     "tabId": tab_id_clone,
-                    "url": url_string.clone()
+                    "url": url_string.clone(),
+                    "containerId": container_id
                 }));
                 
-                // Определяем title на основе URL для быстрого обновления
-                let url_based_title = get_title_from_url(&url_string);
-                
                 // Отправляем предварительное событие обновления title (пока страница не загрузилась)
                 let _ = app_handle.emit("webview-title-changed", serde_json::json!({
                     "tabId": tab_id_clone,
@@ -61,95 +99,165 @@ pub async fn create_tab_webview_impl(
         .on_page_load({
             let app_handle = app.clone();
             let tab_id_clone = tab_id.clone();
-            move |webview, _payload| {
+            move |webview, payload| {
                 println!("🦀 Rust: on_page_load triggered for tab: {}", tab_id_clone);
+                let url = payload.url().to_string();
                 let app_clone = app_handle.clone();
                 let tab_id_clone_inner = tab_id_clone.clone();
-                
-                // Получаем title и favicon после загрузки страницы
-                tauri::async_runtime::spawn(async move {
-                    handle_page_load(app_clone, tab_id_clone_inner, webview).await;
-                });
+                match payload.event() {
+                    PageLoadEvent::Started => {
+                        tauri::async_runtime::spawn(async move {
+                            page_load::load_started(&app_clone, &tab_id_clone_inner, &url).await;
+                            // Новый документ: звук старой страницы больше не играет,
+                            // новая сообщит о своём звуке сама
+                            let _ = set_tab_audio(&app_clone, &tab_id_clone_inner, false).await;
+                        });
+                    }
+                    PageLoadEvent::Finished => {
+                        // Состояние меняем сразу, чтобы title новой страницы не считался устаревшим
+                        registry(&app_handle).update(&tab_id_clone, |tab| tab.load_state = LoadState::Loaded);
+                        
+                        let app_for_timing = app_clone.clone();
+                        let tab_id_for_timing = tab_id_clone_inner.clone();
+                        tauri::async_runtime::spawn(async move {
+                            page_load::load_finished(&app_for_timing, &tab_id_for_timing, &url).await;
+                        });
+                        
+                        // Получаем title и favicon после загрузки страницы
+                        tauri::async_runtime::spawn(async move {
+                            handle_page_load(app_clone, tab_id_clone_inner, webview).await;
+                        });
+                    }
+                }
             }
         })
-        .initialization_script(get_webview_initialization_script());
+        .on_download({
+            let app_handle = app.clone();
+            let tab_id_clone = tab_id.clone();
+            let private_window = is_private.then(|| window_label.clone());
+            move |_webview, event| {
+                match event {
+                    DownloadEvent::Requested { url, destination } => {
+                        let url_string = url.to_string();
+                        if crate::downloads::is_managed_url(&url_string) {
+                            // HTTP-загрузки выполняет менеджер загрузок (прогресс, отмена, повтор),
+                            // поэтому встроенную загрузку webview отменяем
+                            let file_name_hint = destination
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string());
+                            crate::downloads::start_download(&app_handle, url_string, Some(tab_id_clone.clone()), file_name_hint, private_window.clone());
+                            return false;
+                        }
+                        
+                        // blob: и data: может скачать только сам webview
+                        crate::downloads::track_native_download(&app_handle, &url_string, destination, &tab_id_clone, private_window.clone());
+                        true
+                    }
+                    DownloadEvent::Finished { url, success, .. } => {
+                        crate::downloads::finish_native_download(&app_handle, url.as_str(), success);
+                        true
+                    }
+                    _ => true,
+                }
+            }
+        })
+        .initialization_script(get_webview_initialization_script())
+        .initialization_script(get_audio_tracking_script())
+        .initialization_script(get_title_tracking_script());
+    
+    // Вкладки приватного окна получают отдельное временное хранилище данных,
+    // вкладки контейнера — хранилище контейнера, остальные — хранилище текущего профиля
+    if is_private {
+        webview_builder = data_store::with_private_storage(webview_builder, private::private_data_dir(&app, &window_label)?)?;
+    } else if let Some(container_id) = &container_id {
+        webview_builder = data_store::with_storage(webview_builder, containers::container_data_dir(&app, container_id)?)?;
+    } else if let Some(dir) = profile::webview_data_dir(&app)? {
+        webview_builder = data_store::with_storage(webview_builder, dir)?;
+    }
     
-    // Позиционируем под header панелями
-    let position = Position::Logical(LogicalPosition::new(0.0, HEADER_HEIGHT));
-    let size = Size::Logical(LogicalSize::new(
-        window_size.width as f64, 
-        window_size.height as f64 - HEADER_HEIGHT
-    ));
+    // Регистрируем вкладку до создания webview: on_navigation и on_page_load
+    // срабатывают уже во время add_child и должны застать запись в реестре
+    let mut record = TabRecord::new(&tab_id, &webview_label, &window_label, &url, &title, now_millis());
+    record.container_id = container_id.clone();
+    registry(&app).insert(record);
     
     // КРИТИЧЕСКИ ВАЖНО: используем add_child для создания embedded WebView
     // Child WebView автоматически ниже UI родительского окна
-    let webview = main_window.add_child(webview_builder, position, size)
-        .map_err(|e| format!("Failed to create child webview: {}", e))?;
+    let webview = match main_window.add_child(webview_builder, bounds.position(), bounds.size()) {
+        Ok(webview) => webview,
+        Err(e) => {
+            registry(&app).remove(&tab_id).await;
+            return Err(LimniError::internal(format!("Failed to create child webview: {}", e)));
+        }
+    };
     
     // Скрываем webview по умолчанию (но он останется ниже UI)
-    webview.hide().map_err(|e| format!("Failed to hide webview: {}", e))?;
+    webview.hide().map_err(|e| LimniError::internal(format!("Failed to hide webview: {}", e)))?;
     
-    // Сохраняем ссылку на webview
-    let state = app.state::<WebviewState>();
-    let mut webviews = state.webviews.lock().unwrap();
-    webviews.insert(tab_id.clone(), webview_label.clone());
+    // Вкладка создана явно, поэтому отложенное создание из сессии больше не нужно
+    session::take_lazy_tab(&app, &tab_id);
+    if !is_private {
+        session::update(&app, |s| {
+            s.open_tab(&window_label, &tab_id, &url, &title);
+            s.set_container(&tab_id, container_id.as_deref());
+        });
+    }
     
     Ok(webview_label)
 }
 
-/// Показывает webview вкладки
+/// Показывает webview вкладки. `window_label` — окно, в котором создаётся
+/// отложенная вкладка восстановленной сессии
 pub async fn show_tab_webview_impl(
     app: AppHandle,
+    window_label: String,
     tab_id: String,
-) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
-    // Скрываем только ДРУГИЕ webview'ы (не активный)
-    for (other_tab_id, webview_label) in webviews.iter() {
-        if other_tab_id != &tab_id {
-            if let Some(webview) = app.get_webview(webview_label) {
-                let _ = webview.hide(); // Скрываем неактивные WebView
-            }
+) -> Result<(), LimniError> {
+    // Вкладка из восстановленной сессии создаётся только при первом показе
+    if let Some(tab) = session::take_lazy_tab(&app, &tab_id) {
+        create_tab_webview_impl(app.clone(), window_label, tab.tab_id, tab.url, tab.title, tab.container_id).await?;
+        if tab.muted {
+            set_tab_muted(&app, &tab_id, true).await?;
         }
     }
     
-    // Показываем нужный webview если он существует
-    if let Some(webview_label) = webviews.get(&tab_id) {
-        if let Some(webview) = app.get_webview(webview_label) {
-            webview.show().map_err(|e| format!("Failed to show webview: {}", e))?;
-            
-            // Убеждаемся, что webview находится на правильном месте
-            let main_window = app.get_window("main")
-                .ok_or("Main window not found")?;
-            let window_size = main_window.inner_size()
-                .map_err(|e| format!("Failed to get window size: {}", e))?;
-            
-            let position = Position::Logical(LogicalPosition::new(0.0, HEADER_HEIGHT));
-            let size = Size::Logical(LogicalSize::new(
-                window_size.width as f64, 
-                window_size.height as f64 - HEADER_HEIGHT
-            ));
-            
-            let _ = webview.set_position(position);
-            let _ = webview.set_size(size);
-        } else {
-            return Err("Webview not found".to_string());
+    let registry = registry(&app);
+    // Вкладки без webview (about:blank) frontend не показывает через бэкенд,
+    // поэтому неизвестная вкладка — это пропавший webview, который frontend пересоздаст
+    let tab = registry.get(&tab_id).await
+        .ok_or_else(|| LimniError::tab_not_found(&tab_id))?;
+    let window_label = tab.window_label;
+    
+    // Скрываем только ДРУГИЕ webview'ы этого окна (не активный)
+    for other in registry.in_window(&window_label).await {
+        if other.tab_id != tab_id {
+            if let Some(webview) = app.get_webview(&other.webview_label) {
+                let _ = webview.hide(); // Скрываем неактивные WebView
+            }
         }
     }
-    // Если webview для вкладки не найден (например, about:blank), просто скрываем все
+    
+    // Показываем нужный webview
+    let webview = app.get_webview(&tab.webview_label)
+        .ok_or_else(|| LimniError::webview_gone(&tab_id))?;
+    webview.show().map_err(|e| LimniError::internal(format!("Failed to show webview: {}", e)))?;
+    
+    // Убеждаемся, что webview находится на правильном месте
+    let main_window = app.get_window(&window_label)
+        .ok_or_else(|| LimniError::window_not_found(&window_label))?;
+    layout::apply_bounds(&webview, layout::window_content_bounds(&main_window)?);
+    
+    registry.update(&tab_id, |tab| tab.last_active_at = Some(now_millis()));
+    session::update(&app, |s| s.set_active(&tab_id));
     
     Ok(())
 }
 
-/// Скрывает все webview'ы (для about:blank вкладок)
-pub async fn hide_all_webviews_impl(app: AppHandle) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
-    // Скрываем все webview'ы
-    for webview_label in webviews.values() {
-        if let Some(webview) = app.get_webview(webview_label) {
+/// Скрывает все webview'ы окна (для about:blank вкладок)
+pub async fn hide_all_webviews_impl(app: AppHandle, window_label: String) -> Result<(), LimniError> {
+    // Скрываем все webview'ы этого окна
+    for tab in registry(&app).in_window(&window_label).await {
+        if let Some(webview) = app.get_webview(&tab.webview_label) {
             let _ = webview.hide(); // Игнорируем ошибки при скрытии
         }
     }
@@ -161,232 +269,344 @@ pub async fn hide_all_webviews_impl(app: AppHandle) -> Result<(), String> {
 pub async fn close_tab_webview_impl(
     app: AppHandle,
     tab_id: String,
-) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let mut webviews = state.webviews.lock().unwrap();
+) -> Result<(), LimniError> {
+    session::take_lazy_tab(&app, &tab_id);
+    session::update(&app, |s| s.close_tab(&tab_id));
     
-    if let Some(webview_label) = webviews.remove(&tab_id) {
-        if let Some(webview) = app.get_webview(&webview_label) {
-            webview.close().map_err(|e| format!("Failed to close webview: {}", e))?;
+    if let Some(tab) = registry(&app).remove(&tab_id).await {
+        if let Some(webview) = app.get_webview(&tab.webview_label) {
+            webview.close().map_err(|e| LimniError::internal(format!("Failed to close webview: {}", e)))?;
         }
     }
     
     Ok(())
 }
 
+/// Закрывает webview'ы всех вкладок окна
+pub async fn close_window_webviews_impl(app: AppHandle, window_label: String) -> Result<(), LimniError> {
+    // Закрытие последнего окна — это выход из приложения: вкладки остаются в снимке сессии
+    if !app.windows().keys().any(|label| label != &window_label) {
+        return Ok(());
+    }
+    
+    for tab in registry(&app).in_window(&window_label).await {
+        if let Err(e) = close_tab_webview_impl(app.clone(), tab.tab_id).await {
+            println!("🦀 Rust: Failed to close tab webview: {}", e);
+        }
+    }
+    
+    if private::is_private_window(&window_label) {
+        private::wipe_private_data(&app, &window_label);
+    }
+    
+    println!("🦀 Rust: Closed tab webviews of window: {}", window_label);
+    Ok(())
+}
+
+/// Переставляет webview'ы вкладок окна в область под интерфейсом (после изменения размера окна или отступов)
+pub async fn reposition_window_webviews(app: &AppHandle, window_label: &str) {
+    let Some(window) = app.get_window(window_label) else {
+        return;
+    };
+    let Ok(bounds) = layout::window_content_bounds(&window) else {
+        return;
+    };
+    apply_window_bounds(app, window_label, bounds).await;
+}
+
+/// Ставит все webview'ы вкладок окна в заданную область
+async fn apply_window_bounds(app: &AppHandle, window_label: &str, bounds: layout::ContentBounds) {
+    for tab in registry(app).in_window(window_label).await {
+        if let Some(webview) = app.get_webview(&tab.webview_label) {
+            layout::apply_bounds(&webview, bounds);
+        }
+    }
+}
+
+/// Подписывается на события окна: изменение размера или масштаба и уничтожение окна
+pub fn setup_window_events(app: &AppHandle, window: &tauri::Window) {
+    let app_handle = app.clone();
+    let window_label = window.label().to_string();
+    window.on_window_event(move |event| {
+        let app_handle_for_async = app_handle.clone();
+        let window_label = window_label.clone();
+        match event {
+            tauri::WindowEvent::Resized(_) => {
+                // При изменении размера окна обновляем позиции webview'ов только этого окна
+                tauri::async_runtime::spawn(async move {
+                    reposition_window_webviews(&app_handle_for_async, &window_label).await;
+                });
+            }
+            tauri::WindowEvent::ScaleFactorChanged { scale_factor, new_inner_size, .. } => {
+                // Окно перенесли на экран с другим масштабом: размер из события уже новый,
+                // а окно может ещё отдавать старый масштаб
+                let insets = app_handle_for_async.try_state::<layout::LayoutState>()
+                    .map(|state| state.insets(&window_label))
+                    .unwrap_or_default();
+                let bounds = layout::content_bounds(*new_inner_size, *scale_factor, insets);
+                tauri::async_runtime::spawn(async move {
+                    apply_window_bounds(&app_handle_for_async, &window_label, bounds).await;
+                });
+            }
+            tauri::WindowEvent::Destroyed => {
+                if let Some(state) = app_handle_for_async.try_state::<layout::LayoutState>() {
+                    state.remove(&window_label);
+                }
+                // Окно закрыто — закрываем только его вкладки
+                tauri::async_runtime::spawn(async move {
+                    let _ = close_window_webviews_impl(app_handle_for_async, window_label).await;
+                });
+            }
+            _ => {}
+        }
+    });
+}
+
+/// Создает новое окно браузера с теми же параметрами, что и главное окно.
+/// Приватное окно отличается только префиксом метки — по нему вкладки получают временное хранилище
+pub async fn create_new_window_impl(app: AppHandle, is_private: bool) -> Result<String, LimniError> {
+    let window_label = new_window_label(is_private);
+    open_browser_window(&app, &window_label)?;
+    Ok(window_label)
+}
+
+/// Новая метка окна. Счётчик различает окна, открытые в одну миллисекунду
+/// (например, при восстановлении сессии)
+pub fn new_window_label(is_private: bool) -> String {
+    static NEXT_WINDOW: AtomicU64 = AtomicU64::new(0);
+    let prefix = if is_private { private::PRIVATE_WINDOW_PREFIX } else { "window-" };
+    format!("{}{}-{}", prefix, now_millis(), NEXT_WINDOW.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Открывает окно браузера с меткой `window_label`. Приватность окна определяется по метке
+pub fn open_browser_window(app: &AppHandle, window_label: &str) -> Result<(), LimniError> {
+    let is_private = private::is_private_window(window_label);
+    let mut config = app.config().app.windows
+        .iter()
+        .find(|w| w.label == crate::state::MAIN_WINDOW_LABEL)
+        .cloned()
+        .ok_or_else(|| LimniError::window_not_found(crate::state::MAIN_WINDOW_LABEL))?;
+    config.label = window_label.to_string();
+    if is_private {
+        config.title = crate::i18n::t_args(app, "window.private", &[("title", &config.title)]);
+    }
+    
+    let webview_window = tauri::WebviewWindowBuilder::from_config(app, &config)
+        .map_err(|e| LimniError::internal(format!("Failed to configure window: {}", e)))?
+        .build()
+        .map_err(|e| LimniError::internal(format!("Failed to create window: {}", e)))?;
+    
+    setup_window_events(app, &webview_window.as_ref().window());
+    
+    println!("🦀 Rust: Created new window: {}", window_label);
+    Ok(())
+}
+
 /// Навигация в webview
 pub async fn navigate_webview_impl(
     app: AppHandle,
     tab_id: String,
     url: String,
-) -> Result<(), String> {
-    let state = app.state::<WebviewState>();
-    let webviews = state.webviews.lock().unwrap();
-    
-    if let Some(webview_label) = webviews.get(&tab_id) {
-        if let Some(webview) = app.get_webview(webview_label) {
-            // Навигация в существующем webview
-            let webview_url = if url.starts_with("http://") || url.starts_with("https://") || url.starts_with("file://") {
-                url.parse().map_err(|e| format!("Invalid URL: {}", e))?
-            } else {
-                return Err(format!("Unsupported URL scheme for navigation: {}", url));
-            };
-            
-            webview.navigate(webview_url)
-                .map_err(|e| format!("Failed to navigate webview: {}", e))?;
-            
-            println!("🦀 Rust: Navigated existing webview for tab {} to {}", tab_id, url);
-            Ok(())
-        } else {
-            Err("Webview not found".to_string())
-        }
+) -> Result<(), LimniError> {
+    let webview = tab_webview(&app, &tab_id).await?;
+    
+    // Навигация в существующем webview
+    let webview_url = if url.starts_with("http://") || url.starts_with("https://") || url.starts_with("file://") {
+        url.parse().map_err(|e| LimniError::invalid_url(&url, e))?
     } else {
-        Err("Tab not found".to_string())
-    }
+        return Err(LimniError::unsupported_scheme(url));
+    };
+    
+    webview.navigate(webview_url)
+        .map_err(|e| LimniError::internal(format!("Failed to navigate webview: {}", e)))?;
+    
+    println!("🦀 Rust: Navigated existing webview for tab {} to {}", tab_id, url);
+    Ok(())
 }
 
 /// Обрабатывает загрузку страницы
 async fn handle_page_load(app_clone: AppHandle, tab_id_clone_inner: String, webview: tauri::Webview) {
-    // Небольшая задержка для полной загрузки DOM
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let Ok(current_url) = webview.url() else {
+        return;
+    };
+    let url_str = current_url.to_string();
     
-    // Получаем URL для favicon
-    if let Ok(current_url) = webview.url() {
-        let url_str = current_url.to_string();
-        
-        // Надежное получение title через оценку JavaScript
-        let title_script = get_title_detection_script();
-        
-        println!("🦀 Rust: Injecting title script for tab: {}", tab_id_clone_inner);
-        let _ = webview.eval(&title_script);
-        
-        // Детекция медиаконтента
-        let media_detection_script = get_media_detection_script();
-        
-        if let Ok(_) = webview.eval(media_detection_script) {
-            // Поскольку eval возвращает Result сразу, обрабатываем его синхронно
-            println!("🦀 Rust: Media detection executed for tab: {}", tab_id_clone_inner);
-            
-            // Отправляем событие об изменении медиаконтента (по умолчанию true для YouTube)
-            let has_audio = url_str.contains("youtube.com") || url_str.contains("vimeo.com") || url_str.contains("soundcloud.com");
-            let _ = app_clone.emit("webview-audio-changed", serde_json::json!({
-                "tabId": tab_id_clone_inner,
-                "hasAudio": has_audio,
-                "isAudioMuted": false
-            }));
-        }
-        
-        // Устанавливаем периодическую проверку медиаконтента
-        setup_periodic_media_check(app_clone.clone(), tab_id_clone_inner.clone(), webview.clone()).await;
-        
-        // Добавляем задержку для загрузки DOM и затем извлекаем title
-        setup_title_extraction(app_clone.clone(), tab_id_clone_inner.clone(), webview.clone()).await;
-        
-        // Получаем favicon асинхронно
-        if url_str != "about:blank" && url_str.starts_with("http") {
-            let app_clone_favicon = app_clone.clone();
-            let tab_id_favicon = tab_id_clone_inner.clone();
-            let url_for_favicon = url_str.clone();
-            tauri::async_runtime::spawn(async move {
-                match fetch_favicon_backend(url_for_favicon).await {
-                    Ok(favicon_data) => {
-                        let _ = app_clone_favicon.emit("webview-favicon-changed", serde_json::json!({
-                            "tabId": tab_id_favicon,
-                            "favicon": favicon_data
-                        }));
-                    },
-                    Err(err) => {
-                        // Если не удалось получить favicon, отправляем null
-                        let _ = app_clone_favicon.emit("webview-favicon-changed", serde_json::json!({
-                            "tabId": tab_id_favicon,
-                            "favicon": null
-                        }));
-                        
-                        // Проверяем, является ли это критической ошибкой загрузки
-                        if err.to_string().contains("connection") || err.to_string().contains("timeout") {
-                            let error_message = format!("Ошибка загрузки страницы: {}", err);
-                            let _ = app_clone_favicon.emit("webview-load-error", serde_json::json!({
-                                "tabId": tab_id_favicon,
-                                "errorMessage": error_message
-                            }));
-                        }
-                    }
+    // Звук отключается скриптом внутри документа, поэтому каждый новый документ
+    // вкладки с отключённым звуком отключаем заново
+    if registry(&app_clone).get(&tab_id_clone_inner).await.is_some_and(|tab| tab.muted) {
+        let _ = webview.eval(get_mute_script());
+    }
+    
+    // Title читаем из самой страницы
+    if let Err(e) = refresh_tab_title(&app_clone, &tab_id_clone_inner, &url_str).await {
+        println!("🦀 Rust: Failed to read title of tab {}: {}", tab_id_clone_inner, e);
+    }
+    
+    // Получаем favicon асинхронно
+    if url_str != "about:blank" && url_str.starts_with("http") {
+        let app_clone_favicon = app_clone.clone();
+        let tab_id_favicon = tab_id_clone_inner.clone();
+        let url_for_favicon = url_str.clone();
+        tauri::async_runtime::spawn(async move {
+            let private = private::is_private_tab(&app_clone_favicon, &tab_id_favicon).await;
+            match fetch_favicon_backend(&app_clone_favicon, url_for_favicon, private).await {
+                Ok(favicon_data) => {
+                    let favicon = favicon_data.clone();
+                    registry(&app_clone_favicon).update(&tab_id_favicon, move |tab| tab.favicon = Some(favicon));
+                    let _ = app_clone_favicon.emit("webview-favicon-changed", serde_json::json!({
+                        "tabId": tab_id_favicon,
+                        "favicon": favicon_data
+                    }));
+                },
+                Err(err) => {
+                    // Если не удалось получить favicon, отправляем null.
+                    // Ошибки загрузки самой страницы сообщает page_load
+                    println!("🦀 Rust: Failed to fetch favicon for tab {}: {}", tab_id_favicon, err);
+                    registry(&app_clone_favicon).update(&tab_id_favicon, |tab| tab.favicon = None);
+                    let _ = app_clone_favicon.emit("webview-favicon-changed", serde_json::json!({
+                        "tabId": tab_id_favicon,
+                        "favicon": null
+                    }));
                 }
-            });
-        }
+            }
+        });
+    }
+}
 
-        // Асинхронно получаем реальный заголовок страницы и обновляем вкладку
-        if url_str != "about:blank" && url_str.starts_with("http") {
-            let app_clone_title_real = app_clone.clone();
-            let tab_id_title_real = tab_id_clone_inner.clone();
-            let url_for_title = url_str.clone();
-            tauri::async_runtime::spawn(async move {
-                match fetch_page_title_backend(url_for_title).await {
-                    Ok(real_title) => {
-                        let _ = app_clone_title_real.emit("webview-title-changed", serde_json::json!({
-                            "tabId": tab_id_title_real,
-                            "title": real_title
-                        }));
-                        println!("🦀 Rust: Real title set to '{}' for tab: {}", real_title, tab_id_title_real);
-                    },
-                    Err(err) => {
-                        println!("🦀 Rust: Failed to fetch page title: {}", err);
-                    }
-                }
-            });
-        }
+/// Сообщает о новом title вкладки в историю, сессию и frontend
+fn publish_tab_title(app: &AppHandle, tab_id: &str, title: &str) -> Result<(), LimniError> {
+    history::record_title(app, tab_id, title);
+    session::update(app, |s| s.set_title(tab_id, title));
+    
+    app.emit("webview-title-changed", serde_json::json!({
+        "tabId": tab_id,
+        "title": title
+    })).map_err(|e| LimniError::internal(format!("Failed to emit title change event: {}", e)))
+}
+
+/// Title, сообщённый страницей вкладки с адресом `page_url`. Реестр решает,
+/// относится ли он к текущей навигации; frontend получает только изменения
+pub async fn apply_page_title(app: &AppHandle, tab_id: &str, page_url: &str, title: &str) -> Result<(), LimniError> {
+    let page_url = page_url.to_string();
+    let reported = title.to_string();
+    let applied = registry(app)
+        .modify(tab_id, move |tab| tab.apply_page_title(&page_url, &reported).then(|| tab.title.clone()))
+        .await
+        .ok_or_else(|| LimniError::tab_not_found(tab_id))?;
+    
+    if let Some(title) = applied {
+        println!("🦀 Rust: Page title '{}' for tab: {}", title, tab_id);
+        publish_tab_title(app, tab_id, &title)?;
     }
+    Ok(())
 }
 
-/// Устанавливает периодическую проверку медиаконтента
-async fn setup_periodic_media_check(_app_clone: AppHandle, _tab_id: String, webview: tauri::Webview) {
-    tauri::async_runtime::spawn(async move {
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3));
-        let mut check_count = 0;
-        const MAX_CHECKS: u32 = 100; // Ограничиваем количество проверок
-        
-        loop {
-            // Проверяем, не превышен ли лимит проверок
-            if check_count >= MAX_CHECKS {
-                println!("🦀 Rust: Media check limit reached, stopping periodic check");
-                break;
+/// Читает `document.title` загруженной страницы. Для страниц без title
+/// остаётся title из URL
+async fn refresh_tab_title(app: &AppHandle, tab_id: &str, url: &str) -> Result<(), LimniError> {
+    let value = eval_in_tab(app, tab_id, "document.title", DEFAULT_EVAL_TIMEOUT).await?;
+    match value.as_str().filter(|title| !title.trim().is_empty()) {
+        Some(title) => apply_page_title(app, tab_id, url, title).await,
+        None => {
+            let fallback = get_title_from_url(app, url);
+            let title = fallback.clone();
+            let applied = registry(app).modify(tab_id, move |tab| tab.apply_fallback_title(&title)).await;
+            if applied == Some(true) {
+                publish_tab_title(app, tab_id, &fallback)?;
             }
+            Ok(())
+        }
+    }
+}
+
+/// Отключает или включает звук вкладки и запоминает это в реестре и снимке сессии
+pub async fn set_tab_muted(app: &AppHandle, tab_id: &str, muted: bool) -> Result<(), LimniError> {
+    let webview = tab_webview(app, tab_id).await?;
+    
+    // Выполняем JavaScript для отключения/включения звука всех медиаэлементов
+    let script = if muted { get_mute_script() } else { get_unmute_script() };
+    webview.eval(script)
+        .map_err(|e| LimniError::internal(format!("Failed to set webview mute: {}", e)))?;
+    
+    registry(app).update(tab_id, move |tab| tab.muted = muted);
+    session::update(app, |s| s.set_muted(tab_id, muted));
+    println!("🦀 Rust: Webview {} for tab: {}", if muted { "muted" } else { "unmuted" }, tab_id);
+    Ok(())
+}
+
+/// Запоминает, играет ли во вкладке звук, и сообщает во frontend, если это изменилось
+pub async fn set_tab_audio(app: &AppHandle, tab_id: &str, has_audio: bool) -> Result<(), LimniError> {
+    // Сравнение и запись одним изменением реестра: одновременные сообщения
+    // не должны оба увидеть старое значение
+    let changed = registry(app)
+        .modify(tab_id, move |tab| {
+            let changed = tab.has_audio != has_audio;
+            tab.has_audio = has_audio;
+            changed.then_some(tab.muted)
+        })
+        .await
+        .ok_or_else(|| LimniError::tab_not_found(tab_id))?;
+    
+    let Some(muted) = changed else {
+        return Ok(());
+    };
+    app.emit("webview-audio-changed", serde_json::json!({
+        "tabId": tab_id,
+        "hasAudio": has_audio,
+        "isAudioMuted": muted
+    })).map_err(|e| LimniError::internal(format!("Failed to emit audio change event: {}", e)))
+}
+
+/// Возвращает скрипт для отключения звука
+fn get_mute_script() -> &'static str {
+    r#"
+        (function() {
+            // Отключаем звук всех audio элементов
+            document.querySelectorAll('audio').forEach(audio => {
+                audio.muted = true;
+            });
             
-            interval.tick().await;
-            check_count += 1;
-            
-            let periodic_script = r#"
-                (function() {
-                    let hasAudio = false;
-                    
-                    // Проверяем audio элементы
-                    document.querySelectorAll('audio').forEach(audio => {
-                        if (!audio.paused && audio.currentTime > 0) {
-                            hasAudio = true;
-                        }
-                    });
-                    
-                    // Проверяем video элементы
-                    document.querySelectorAll('video').forEach(video => {
-                        if (!video.paused && video.currentTime > 0) {
-                            hasAudio = true;
-                        }
-                    });
-                    
-                    // Специальная проверка для YouTube
-                    if (window.location.hostname.includes('youtube.com')) {
-                        const player = document.querySelector('video');
-                        if (player && !player.paused && player.currentTime > 0) {
-                            hasAudio = true;
-                        }
-                    }
-                    
-                    return hasAudio;
-                })()
-            "#;
+            // Отключаем звук всех video элементов
+            document.querySelectorAll('video').forEach(video => {
+                video.muted = true;
+            });
             
-            // Проверяем, что webview еще доступен
-            if webview.eval(periodic_script).is_err() {
-                println!("🦀 Rust: WebView no longer accessible, stopping media check");
-                break;
-            }
+            // Для YouTube и других сайтов с iframe
+            document.querySelectorAll('iframe').forEach(iframe => {
+                try {
+                    iframe.contentWindow.postMessage('{"event":"command","func":"mute","args":[]}', '*');
+                } catch(e) {}
+            });
             
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        }
-    });
+            console.log('🔇 Tab muted');
+        })();
+    "#
 }
 
-/// Устанавливает извлечение title после загрузки
-async fn setup_title_extraction(app_clone: AppHandle, tab_id: String, webview: tauri::Webview) {
-    tauri::async_runtime::spawn(async move {
-        // Ждем загрузки DOM
-        tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-        
-        // Извлекаем title после загрузки
-        let extract_title_script = r#"
-            (function() {
-                const title = document.title || document.querySelector('title')?.textContent || 'Новая вкладка';
-                console.log('🌐 WebView: Title extraction after load:', title);
-                return title;
-            })()
-        "#;
-        
-        if let Ok(_) = webview.eval(extract_title_script) {
-            // Получаем URL страницы для определения title
-            if let Ok(current_url) = webview.url() {
-                let url_str = current_url.to_string();
-                let fallback_title = get_title_from_url(&url_str);
-                
-                // Отправляем событие обновления title
-                let _ = app_clone.emit("webview-title-changed", serde_json::json!({
-                    "tabId": tab_id,
-                    "title": fallback_title
-                }));
-                
-                println!("🦀 Rust: Fallback title set to '{}' for tab: {}", fallback_title, tab_id);
-            }
-        }
-    });
+/// Возвращает скрипт для включения звука
+fn get_unmute_script() -> &'static str {
+    r#"
+        (function() {
+            // Включаем звук всех audio элементов
+            document.querySelectorAll('audio').forEach(audio => {
+                audio.muted = false;
+            });
+            
+            // Включаем звук всех video элементов
+            document.querySelectorAll('video').forEach(video => {
+                video.muted = false;
+            });
+            
+            // Для YouTube и других сайтов с iframe
+            document.querySelectorAll('iframe').forEach(iframe => {
+                try {
+                    iframe.contentWindow.postMessage('{"event":"command","func":"unMute","args":[]}', '*');
+                } catch(e) {}
+            });
+            
+            console.log('🔊 Tab unmuted');
+        })();
+    "#
 }
 
 /// Возвращает скрипт инициализации для webview
@@ -456,91 +676,94 @@ fn get_webview_initialization_script() -> &'static str {
     "#
 }
 
-/// Возвращает скрипт для детекции title
-fn get_title_detection_script() -> String {
+/// Возвращает скрипт, который следит за audio/video элементами страницы
+/// и сообщает бэкенду, играет ли звук. События медиа не всплывают, поэтому
+/// слушаем их на фазе перехвата у document — так учитываются и элементы,
+/// добавленные после загрузки. `muted` не учитывается: его выставляет
+/// отключение звука вкладки, и индикатор звука при этом должен остаться
+fn get_audio_tracking_script() -> &'static str {
     r#"
         (function() {
-            console.log('🌐 WebView: Executing title detection script');
+            if (window.__limniAudioTracker) return;
+            window.__limniAudioTracker = true;
             
-            const title = document.title || document.querySelector('title')?.textContent || 'Новая вкладка';
-            console.log('🌐 WebView: Found title:', title);
+            var lastReported = false;
+            var scheduled = false;
             
-            // Сохраняем последний title для отслеживания изменений
-            window._lastDetectedTitle = title;
+            function isAudible(media) {
+                return !media.paused && !media.ended && media.volume > 0;
+            }
             
-            // Устанавливаем наблюдатель за изменениями title
-            if (!window._titleObserverSet) {
-                // MutationObserver для отслеживания изменений в title
-                const titleObserver = new MutationObserver(() => {
-                    const newTitle = document.title || document.querySelector('title')?.textContent || 'Новая вкладка';
-                    if (newTitle !== window._lastDetectedTitle) {
-                        console.log('🌐 WebView: Title changed to:', newTitle);
-                        window._lastDetectedTitle = newTitle;
-                        window._pendingTitleUpdate = newTitle;
-                    }
-                });
+            function report() {
+                scheduled = false;
+                var playing = Array.prototype.some.call(document.querySelectorAll('audio, video'), isAudible);
+                if (playing === lastReported) return;
+                lastReported = playing;
                 
-                titleObserver.observe(document.querySelector('head') || document.documentElement, {
-                    subtree: true,
-                    childList: true
-                });
-                
-                // Также отслеживаем прямые изменения document.title
-                let originalTitleSetter = Object.getOwnPropertyDescriptor(Document.prototype, 'title')?.set;
-                if (originalTitleSetter) {
-                    Object.defineProperty(document, 'title', {
-                        set: function(value) {
-                            originalTitleSetter.call(this, value);
-                            console.log('🌐 WebView: Title set via property to:', value);
-                            window._lastDetectedTitle = value;
-                            window._pendingTitleUpdate = value;
-                        },
-                        get: function() {
-                            return this.querySelector('title')?.textContent || '';
-                        }
-                    });
+                var ipc = window.__TAURI_INTERNALS__;
+                if (ipc) {
+                    ipc.invoke('report_tab_audio', { playing: playing }).catch(function() {});
                 }
-                
-                window._titleObserverSet = true;
             }
             
-            // Возвращаем title для немедленной обработки
-            return title;
+            // Несколько событий подряд (например, pause + play при перемотке) дают один отчёт
+            function scheduleReport() {
+                if (scheduled) return;
+                scheduled = true;
+                setTimeout(report, 0);
+            }
+            
+            ['play', 'playing', 'pause', 'ended', 'volumechange', 'emptied'].forEach(function(type) {
+                document.addEventListener(type, scheduleReport, true);
+            });
+            
+            // Удалённый из DOM элемент продолжает играть, поэтому проверяем и при удалении узлов
+            document.addEventListener('DOMContentLoaded', function() {
+                new MutationObserver(function() {
+                    if (lastReported) scheduleReport();
+                }).observe(document.documentElement, { childList: true, subtree: true });
+            });
         })();
-    "#.to_string()
+    "#
 }
 
-/// Возвращает скрипт для детекции медиаконтента
-fn get_media_detection_script() -> &'static str {
+/// Возвращает скрипт, который сообщает бэкенду `document.title` страницы
+/// после загрузки DOM и при каждом его изменении. Частые изменения
+/// (бегущие строки, счётчики в title) сводятся к одному сообщению за интервал
+fn get_title_tracking_script() -> &'static str {
     r#"
         (function() {
-            let hasAudio = false;
+            if (window.__limniTitleTracker) return;
+            window.__limniTitleTracker = true;
             
-            // Проверяем audio элементы
-            document.querySelectorAll('audio').forEach(audio => {
-                if (!audio.paused || audio.readyState > 0) {
-                    hasAudio = true;
-                }
-            });
+            var lastReported = null;
+            var timer = null;
             
-            // Проверяем video элементы
-            document.querySelectorAll('video').forEach(video => {
-                if (!video.paused || video.readyState > 0) {
-                    hasAudio = true;
+            function report() {
+                timer = null;
+                var title = (document.title || '').replace(/\s+/g, ' ').trim();
+                if (!title || title === lastReported) return;
+                lastReported = title;
+                
+                var ipc = window.__TAURI_INTERNALS__;
+                if (ipc) {
+                    ipc.invoke('update_webview_title', { title: title, url: window.location.href }).catch(function() {});
                 }
-            });
-            
-            // Проверяем iframe (YouTube, Vimeo и т.д.)
-            if (document.querySelectorAll('iframe[src*="youtube.com"], iframe[src*="vimeo.com"], iframe[src*="soundcloud.com"]').length > 0) {
-                hasAudio = true;
             }
             
-            // Проверяем Web Audio API
-            if (window.AudioContext || window.webkitAudioContext) {
-                hasAudio = true;
+            function scheduleReport() {
+                if (timer !== null) return;
+                timer = setTimeout(report, 250);
             }
             
-            return hasAudio;
-        })()
+            document.addEventListener('DOMContentLoaded', function() {
+                scheduleReport();
+                new MutationObserver(scheduleReport).observe(document.head || document.documentElement, {
+                    subtree: true,
+                    childList: true,
+                    characterData: true
+                });
+            });
+        })();
     "#
-}
\ No newline at end of file
+}
@@ -4,9 +4,22 @@ import type {
   Bookmark,
   HistoryEntry,
   BrowserSettings,
+  LimniError,
+  LimniErrorCode,
+  PageLoadStartedEvent,
+  PageLoadFinishedEvent,
+  PageLoadFailedEvent,
+  RestoredTab,
 } from '../types/browser.js';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+
+// Проверяет, что ошибка команды — LimniError с одним из указанных кодов
+export function isLimniError(error: unknown, ...codes: LimniErrorCode[]): error is LimniError {
+  if (typeof error !== 'object' || error === null || !('code' in error)) return false;
+  return codes.length === 0 || codes.includes((error as LimniError).code);
+}
 
 // Генерация уникального ID
 function generateId(): string {
@@ -20,6 +33,8 @@ function isGenericTitle(title: string | undefined, url: string | undefined): boo
 
   const genericTitles = new Set([
     'Новая вкладка',
+    'New Tab',
+    'Nova aba',
     'Без названия',
     'Яндекс',
     'Google',
@@ -27,6 +42,8 @@ function isGenericTitle(title: string | undefined, url: string | undefined): boo
     'Microsoft Bing',
     'Загрузка...',
     'Загрузка',
+    'Loading...',
+    'Carregando...',
   ]);
 
   const trimmed = title.trim();
@@ -66,7 +83,8 @@ function decodeHtmlEntities(str: string | undefined): string {
 function isLoadingPlaceholder(title: string | undefined): boolean {
   if (!title) return false;
   const trimmed = title.trim();
-  return trimmed === 'Загрузка...' || trimmed === 'Загрузка';
+  // Заголовок-заглушка бэкенда на любом из языков интерфейса
+  return ['Загрузка...', 'Загрузка', 'Loading...', 'Carregando...'].includes(trimmed);
 }
 
 // Создание нового таба
@@ -133,6 +151,10 @@ export const settings = $state<BrowserSettings>({
   allowJavaScript: true,
   allowImages: true,
   allowCookies: true,
+  proxy: '',
+  faviconFallback: 'letter',
+  faviconServiceUrl: 'https://icons.duckduckgo.com/ip3/{host}.ico',
+  language: 'system',
 });
 
 // Функции для работы с вкладками
@@ -218,7 +240,21 @@ export async function setActiveTab(tabId: string): Promise<void> {
     if (activeTab) {
       if (activeTab.webviewLabel && activeTab.url !== 'about:blank') {
         // Показываем webview для активной вкладки с URL
-        await invoke('show_tab_webview', { tabId: tabId });
+        try {
+          await invoke('show_tab_webview', { tabId: tabId });
+        } catch (error) {
+          if (!isLimniError(error, 'WEBVIEW_GONE', 'TAB_NOT_FOUND')) throw error;
+          // Webview пропал (например, после сбоя процесса) — пересоздаём его
+          await invoke('close_tab_webview', { tabId: tabId });
+          // Контейнер передаём заново, иначе вкладка вернётся в общее хранилище cookies
+          activeTab.webviewLabel = await invoke<string>('create_tab_webview', {
+            tabId: tabId,
+            url: activeTab.url,
+            title: activeTab.title,
+            containerId: activeTab.containerId ?? null,
+          });
+          await invoke('show_tab_webview', { tabId: tabId });
+        }
       } else {
         // Скрываем все webview'ы для about:blank или вкладок без webview
         await invoke('hide_all_webviews');
@@ -255,6 +291,13 @@ export async function updateTabUrl(tabId: string, url: string, _title?: string):
         });
         console.log('🔄 Navigated existing webview for tab:', tabId, 'to:', url);
       } catch (error) {
+        if (isLimniError(error, 'INVALID_URL', 'UNSUPPORTED_SCHEME')) {
+          // Адрес не подходит для навигации: пересоздание webview не поможет
+          tab.isLoading = false;
+          tab.hasError = true;
+          tab.errorMessage = error.message;
+          return;
+        }
         console.error('Navigation failed, creating new webview:', error);
         // Если навигация не удалась, создаем новый webview
         await invoke('close_tab_webview', { tabId: tabId });
@@ -623,38 +666,70 @@ export function canGoForward(tabId: string): boolean {
   return tab ? tab.historyIndex < tab.history.length - 1 : false;
 }
 
-// Инициализация - создаем первую вкладку
-if (browserState.tabs.length === 0) {
-  addTab('about:blank', 'Новая вкладка');
+// Инициализация: восстанавливаем вкладки прошлого запуска, без них создаём первую вкладку.
+// Бэкенд не сохраняет сессию, пока её не забрали, поэтому восстановление идёт первым
+async function initializeTabs(): Promise<void> {
+  try {
+    const restored = await invoke<RestoredTab[]>('restore_session');
+    for (const saved of restored) {
+      const tab = createTab(saved.url, saved.title);
+      tab.id = saved.tabId;
+      tab.webviewLabel = saved.webviewLabel;
+      tab.isAudioMuted = saved.muted;
+      tab.containerId = saved.containerId;
+      browserState.tabs.push(tab);
+    }
+    const active = restored.find((saved) => saved.active) ?? restored[0];
+    if (active) {
+      await setActiveTab(active.tabId);
+    }
+  } catch (error) {
+    console.error('Failed to restore session:', error);
+  }
+
+  if (browserState.tabs.length === 0) {
+    addTab('about:blank', 'Новая вкладка');
+  }
 }
 
-// Настройка обработчика событий изменения URL в webview
-listen<{ tabId: string; url: string }>('webview-url-changed', (event) => {
-  const { tabId, url } = event.payload;
-  const tab = browserState.tabs.find((t) => t.id === tabId);
-  if (tab) {
-    tab.url = url;
-    // Не сбрасываем isLoading здесь – ждём favicon
+initializeTabs();
 
-    // Обновляем историю вкладки если URL изменился
-    if (tab.history[tab.historyIndex] !== url) {
-      tab.history = tab.history.slice(0, tab.historyIndex + 1);
-      tab.history.push(url);
-      tab.historyIndex = tab.history.length - 1;
-    }
+// Настройка обработчика событий изменения URL в webview
+listen<{ tabId: string; url: string; containerId: string | null }>(
+  'webview-url-changed',
+  (event) => {
+    const { tabId, url, containerId } = event.payload;
+    const tab = browserState.tabs.find((t) => t.id === tabId);
+    if (tab) {
+      tab.url = url;
+      tab.containerId = containerId;
+      // Не сбрасываем isLoading здесь – ждём favicon
+
+      // Обновляем историю вкладки если URL изменился
+      if (tab.history[tab.historyIndex] !== url) {
+        tab.history = tab.history.slice(0, tab.historyIndex + 1);
+        tab.history.push(url);
+        tab.historyIndex = tab.history.length - 1;
+      }
 
-    // Добавляем в глобальную историю если это не about:blank
-    if (url !== 'about:blank') {
-      addToHistory(tab.title, url);
+      // Добавляем в глобальную историю если это не about:blank
+      if (url !== 'about:blank') {
+        addToHistory(tab.title, url);
+      }
     }
   }
-});
+);
 
 // Обработка создания новых вкладок из webview
-listen<{ tabId: string; url: string; title: string; webviewLabel: string }>(
+listen<{ tabId: string; url: string; title: string; webviewLabel: string; windowLabel?: string }>(
   'new-tab-created',
   (event) => {
-    const { tabId, url, title, webviewLabel } = event.payload;
+    const { tabId, url, title, webviewLabel, windowLabel } = event.payload;
+
+    // Вкладка принадлежит другому окну
+    if (windowLabel && windowLabel !== getCurrentWindow().label) {
+      return;
+    }
 
     // Создаем новую вкладку с полученными данными
     const newTab: Tab = {
@@ -782,6 +857,7 @@ export function setTabError(tabId: string, errorMessage: string) {
   if (tab) {
     tab.hasError = true;
     tab.errorMessage = errorMessage;
+    tab.errorDescription = undefined;
     tab.isLoading = false; // Останавливаем индикатор загрузки
   }
 }
@@ -792,6 +868,8 @@ export function clearTabError(tabId: string) {
   if (tab) {
     tab.hasError = false;
     tab.errorMessage = undefined;
+    tab.errorDescription = undefined;
+    tab.errorKind = undefined;
   }
 }
 
@@ -807,12 +885,73 @@ listen<{ tabId: string; isMuted: boolean }>('webview-mute-changed', (event) => {
   setTabAudioState(tabId, isMuted);
 });
 
-// Обработчик событий ошибки загрузки
-listen<{ tabId: string; errorMessage: string }>('webview-load-error', (event) => {
-  console.error(`Ошибка загрузки для вкладки ${event.payload.tabId}:`, event.payload.errorMessage);
-  setTabError(event.payload.tabId, event.payload.errorMessage);
+// Жизненный цикл загрузки страницы. События завершения относятся к навигации,
+// поэтому запоздавшие события предыдущей навигации игнорируются
+function isCurrentNavigation(tab: Tab, navigationId: number): boolean {
+  return tab.navigationId === undefined || tab.navigationId === navigationId;
+}
+
+listen<PageLoadStartedEvent>('page-load-started', (event) => {
+  const tab = getTab(event.payload.tabId);
+  if (tab) {
+    tab.navigationId = event.payload.navigationId;
+    tab.isLoading = true;
+    tab.httpStatus = undefined;
+    clearTabError(tab.id);
+  }
+});
+
+listen<PageLoadFinishedEvent>('page-load-finished', (event) => {
+  const tab = getTab(event.payload.tabId);
+  if (tab && isCurrentNavigation(tab, event.payload.navigationId)) {
+    tab.isLoading = false;
+    tab.httpStatus = event.payload.status ?? undefined;
+  }
 });
 
+listen<PageLoadFailedEvent>('page-load-failed', (event) => {
+  const tab = getTab(event.payload.tabId);
+  if (tab && isCurrentNavigation(tab, event.payload.navigationId)) {
+    console.error(`Ошибка загрузки для вкладки ${tab.id}:`, event.payload.reason);
+    tab.httpStatus = event.payload.status ?? undefined;
+    tab.errorKind = event.payload.errorKind;
+    setTabError(tab.id, event.payload.message);
+    tab.errorDescription = event.payload.description;
+  }
+});
+
+// Повторяет последнюю навигацию вкладки со страницы ошибки. Бэкенд сначала
+// проверяет адрес и, пока сайт недоступен, возвращает ошибку NETWORK
+export async function retryNavigation(tabId: string): Promise<boolean> {
+  const tab = getTab(tabId);
+  if (!tab) return false;
+
+  tab.isLoading = true;
+  try {
+    await invoke('retry_navigation', { tabId });
+    clearTabError(tabId);
+    return true;
+  } catch (error) {
+    console.error(`Retry failed for tab ${tabId}:`, error);
+    tab.isLoading = false;
+    if (!isLimniError(error, 'NETWORK')) {
+      setTabError(tabId, isLimniError(error) ? error.message : String(error));
+    }
+    return false;
+  }
+}
+
+// Когда сеть возвращается, повторяем загрузку вкладок со страницей ошибки
+if (typeof window !== 'undefined') {
+  window.addEventListener('online', () => {
+    for (const tab of browserState.tabs) {
+      if (tab.hasError && tab.webviewLabel) {
+        retryNavigation(tab.id);
+      }
+    }
+  });
+}
+
 // Функция для получения вкладки по ID
 function getTab(tabId: string) {
   return browserState.tabs.find((tab) => tab.id === tabId);
@@ -10,8 +10,13 @@ export interface Tab {
   webviewLabel?: string; // Метка нативного webview
   hasAudio?: boolean; // Есть ли звук на вкладке
   isAudioMuted?: boolean; // Заглушен ли звук
+  containerId?: string | null; // Контейнер, в хранилище которого открыта вкладка
   hasError?: boolean; // Есть ли ошибка загрузки
   errorMessage?: string; // Сообщение об ошибке
+  errorDescription?: string; // Пояснение к ошибке загрузки на языке интерфейса (из page-load-failed)
+  navigationId?: number; // Номер текущей навигации (из page-load-started)
+  httpStatus?: number; // HTTP статус последней загрузки, если известен
+  errorKind?: NavigationErrorKind; // Класс ошибки загрузки (из page-load-failed)
 }
 
 export interface BrowserState {
@@ -49,6 +54,183 @@ export interface BrowserSettings {
   allowJavaScript: boolean;
   allowImages: boolean;
   allowCookies: boolean;
+  // HTTP(S) прокси для запросов бэкенда, пустая строка — без прокси
+  proxy: string;
+  // Значок для сайтов без своего: локальная буква, внешний сервис или ничего
+  faviconFallback: 'letter' | 'service' | 'none';
+  // Шаблон адреса сервиса значков с {host}
+  faviconServiceUrl: string;
+  // Язык интерфейса: язык системы или один из поддерживаемых
+  language: 'system' | 'en' | 'ru' | 'pt-BR';
+}
+
+// Стабильные коды ошибок команд бэкенда
+export type LimniErrorCode =
+  | 'TAB_NOT_FOUND'
+  | 'WEBVIEW_GONE'
+  | 'WINDOW_NOT_FOUND'
+  | 'INVALID_URL'
+  | 'UNSUPPORTED_SCHEME'
+  | 'NOT_FOUND'
+  | 'INVALID_ARGUMENT'
+  | 'NETWORK'
+  | 'IO'
+  | 'TIMEOUT'
+  | 'SCRIPT_ERROR'
+  | 'FORBIDDEN'
+  | 'INTERNAL';
+
+// Ошибка, которую возвращают команды бэкенда (LimniError)
+export interface LimniError {
+  code: LimniErrorCode;
+  message: string;
+  tabId?: string;
+  windowLabel?: string;
+  url?: string;
+  entity?: string;
+  id?: string;
+}
+
+// Тайминги загрузки документа (Navigation Timing API), мс от начала навигации
+export interface NavigationTiming {
+  status: number | null;
+  redirectCount: number;
+  timeToFirstByte: number | null;
+  domContentLoaded: number | null;
+  load: number | null;
+  transferSize: number | null;
+}
+
+// События жизненного цикла загрузки страницы
+export interface PageLoadStartedEvent {
+  tabId: string;
+  navigationId: number;
+  url: string;
+  startedAt: number;
+}
+
+export interface PageLoadFinishedEvent {
+  tabId: string;
+  navigationId: number;
+  url: string;
+  status: number | null;
+  timing: NavigationTiming | null;
+  elapsedMs: number | null;
+}
+
+// Класс ошибки загрузки страницы
+export type NavigationErrorKind =
+  | 'dns'
+  | 'connectionRefused'
+  | 'timeout'
+  | 'tls'
+  | 'serverError'
+  | 'connectionFailed';
+
+export interface PageLoadFailedEvent {
+  tabId: string;
+  navigationId: number;
+  url: string;
+  status: number | null;
+  errorKind: NavigationErrorKind;
+  reason: string;
+  message: string; // Заголовок ошибки на языке интерфейса
+  description: string; // Пояснение к ошибке на языке интерфейса
+  elapsedMs: number | null;
+}
+
+// Отступы интерфейса от краёв окна (логические пиксели), под ними не рисуются webview'ы вкладок
+export interface ChromeInsets {
+  top: number;
+  left: number;
+  right: number;
+  bottom: number;
+}
+
+// Метаданные страницы из её <head> (команда get_page_metadata)
+export interface OpenGraph {
+  title: string | null;
+  description: string | null;
+  image: string | null;
+  url: string | null;
+  siteName: string | null;
+  kind: string | null;
+}
+
+export interface TwitterCard {
+  card: string | null;
+  title: string | null;
+  description: string | null;
+  image: string | null;
+  site: string | null;
+  creator: string | null;
+}
+
+export interface FeedLink {
+  url: string;
+  title: string | null;
+  kind: 'rss' | 'atom' | 'json';
+}
+
+export interface PageMetadata {
+  url: string;
+  title: string | null;
+  description: string | null;
+  canonicalUrl: string | null;
+  openGraph: OpenGraph;
+  twitter: TwitterCard;
+  themeColor: string | null;
+  language: string | null;
+  feeds: FeedLink[];
+  manifestUrl: string | null;
+}
+
+// Вкладка сессии прошлого запуска (restore_session)
+export interface RestoredTab {
+  tabId: string;
+  url: string;
+  title: string;
+  pinned: boolean;
+  muted: boolean;
+  containerId: string | null;
+  windowLabel: string;
+  webviewLabel: string;
+  active: boolean; // Webview активной вкладки уже создан, остальные создаются при показе
+}
+
+export interface LocaleInfo {
+  locale: string;
+  systemLocale: string;
+  available: string[];
+}
+
+export interface SiteNames {
+  builtin: Record<string, string>;
+  overrides: Record<string, string>;
+}
+
+// Запись менеджера загрузок (get_downloads и события download-*)
+export type DownloadStatus = 'inProgress' | 'completed' | 'failed' | 'cancelled';
+
+export interface DownloadItem {
+  id: string;
+  url: string;
+  fileName: string;
+  path: string | null;
+  status: DownloadStatus;
+  receivedBytes: number;
+  totalBytes: number | null;
+  error: string | null;
+  tabId: string | null;
+  startedAt: number;
+  finishedAt: number | null;
+  private: boolean;
+}
+
+export interface DownloadProgressEvent {
+  id: string;
+  receivedBytes: number;
+  totalBytes: number | null;
 }
 
 // Типы для webview команд
@@ -15,6 +15,7 @@ export type HotkeyHandler = {
   shouldIgnoreEvent: (event: KeyboardEvent) => boolean;
   handleKeydownEvent: (event: KeyboardEvent) => Promise<void>;
   handleNewWindow: () => Promise<void>;
+  handleNewPrivateWindow: () => Promise<void>;
   handleCloseWindow: () => Promise<void>;
 };
 
@@ -53,6 +54,12 @@ export function createHotkeyHandler(): HotkeyHandler {
     await invoke('create_new_window');
   }
 
+  // Обработчик открытия нового приватного окна
+  async function handleNewPrivateWindow(): Promise<void> {
+    console.log('🕶️ Горячие клавиши: создание приватного окна');
+    await invoke('create_private_window');
+  }
+
   // Обработчик закрытия текущего окна
   async function handleCloseWindow(): Promise<void> {
     console.log('✖️ Горячие клавиши: закрытие текущего окна');
@@ -152,11 +159,10 @@ export function createHotkeyHandler(): HotkeyHandler {
       return;
     }
 
-    // Ctrl + Shift + N (или Cmd + Shift + N на Mac) - новое приватное окно (в будущем)
+    // Ctrl + Shift + N (или Cmd + Shift + N на Mac) - новое приватное окно
     if ((event.ctrlKey || event.metaKey) && event.shiftKey && event.key === 'N') {
       event.preventDefault();
-      // await handleNewPrivateWindow(); // TODO: Реализовать приватное окно
-      console.log('Private window hotkey pressed (Ctrl+Shift+N)');
+      await handleNewPrivateWindow();
       return;
     }
 
@@ -206,6 +212,7 @@ export function createHotkeyHandler(): HotkeyHandler {
     shouldIgnoreEvent,
     handleKeydownEvent,
     handleNewWindow,
+    handleNewPrivateWindow,
     handleCloseWindow,
   };
 }